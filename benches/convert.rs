@@ -0,0 +1,175 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+
+use anyhow::anyhow;
+use criterion::{criterion_group, criterion_main, Criterion};
+use grib2csv::{Boundary, DataRepresentation, Grib2Csv, ValidationPolicy};
+
+const SAMPLE_FILE: &str = "fixtures/sample.bin";
+
+/// サンプルファイルの第7節を展開する処理の性能を計測する。
+fn decode_sample(c: &mut Criterion) {
+    let grib2 = Grib2Csv::new(SAMPLE_FILE, false).unwrap();
+    let output = std::env::temp_dir().join(format!("grib2csv_bench_{}.csv", std::process::id()));
+
+    c.bench_function("convert sample", |b| {
+        b.iter(|| grib2.convert(&output, Boundary::default()).unwrap())
+    });
+    c.bench_function("grid sample", |b| b.iter(|| grib2.grid().unwrap()));
+    c.bench_function("points sample", |b| {
+        b.iter(|| {
+            grib2
+                .points()
+                .unwrap()
+                .filter(|point| point.as_ref().unwrap().value.is_some())
+                .count()
+        })
+    });
+
+    // フィルターで変換を計測しなかった場合は、出力ファイルが存在しない
+    let _ = std::fs::remove_file(output);
+}
+
+/// メモリに読み込んだサンプルファイルの第7節を展開する処理の性能を、ファイルの入出力を含めずに計測する。
+fn decode_sample_in_memory(c: &mut Criterion) {
+    let bytes = std::fs::read(SAMPLE_FILE).unwrap();
+    let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+
+    c.bench_function("grid sample in memory", |b| {
+        b.iter(|| grib2.grid().unwrap())
+    });
+    c.bench_function("points sample in memory", |b| {
+        b.iter(|| {
+            grib2
+                .points()
+                .unwrap()
+                .filter(|point| point.as_ref().unwrap().value.is_some())
+                .count()
+        })
+    });
+}
+
+/// 第7節を1オクテットずつリーダーから読み込み、1セットごとにランレングス値の`Vec`を確保する、
+/// 第7節をメモリに読み込む前の展開方法の性能を計測する。
+///
+/// "grid sample"と比較して、第7節をまとめて読み込む展開方法の効果を確認する。
+fn decode_sample_baseline(c: &mut Criterion) {
+    let grib2 = Grib2Csv::new(SAMPLE_FILE, false).unwrap();
+    let metadata = grib2.metadata();
+    let DataRepresentation::RunLength(run_length) = &metadata.section5.data_representation else {
+        panic!("the data representation is not a run length");
+    };
+    assert_eq!(run_length.bits_per_data, 8);
+    let number_of_points = metadata.section3.number_of_points;
+    let (offset, length) = section7_data(&std::fs::read(SAMPLE_FILE).unwrap());
+
+    let decode = || {
+        let mut reader = BufReader::new(File::open(SAMPLE_FILE).unwrap());
+        reader.seek(SeekFrom::Start(offset)).unwrap();
+        let mut values = vec![None; number_of_points as usize];
+        let mut index = 0usize;
+        decode_run_length_per_byte(
+            &mut reader,
+            length,
+            run_length.max_level_at_file,
+            number_of_points,
+            |level, count| {
+                let value = run_length.representative_value(level);
+                values[index..index + count as usize].fill(value);
+                index += count as usize;
+            },
+        )
+        .unwrap();
+        values
+    };
+    // 比較する展開方法と同じ物理値を展開することを確認（欠測値はNaN）
+    let expected: Vec<Option<f64>> = grib2
+        .grid()
+        .unwrap()
+        .values()
+        .iter()
+        .map(|&value| (!value.is_nan()).then_some(value))
+        .collect();
+    assert_eq!(decode(), expected);
+
+    c.bench_function("grid sample per-byte baseline", |b| b.iter(decode));
+}
+
+/// GRIB2報の第7節の資料値の開始位置とバイト数を返却する。
+fn section7_data(bytes: &[u8]) -> (u64, u64) {
+    // 第0節の後から、節の長さを利用して第7節まで移動
+    let mut position = 16;
+    loop {
+        let length = u32::from_be_bytes(bytes[position..position + 4].try_into().unwrap());
+        if bytes[position + 4] == 7 {
+            return ((position + 5) as u64, length as u64 - 5);
+        }
+        position += length as usize;
+    }
+}
+
+/// 1データのビット数が8のランレングス圧縮オクテット列を、1オクテットずつ読み込みながら展開する。
+fn decode_run_length_per_byte<R, F>(
+    reader: &mut R,
+    length: u64,
+    maxv: u16,
+    number_of_points: u32,
+    mut f: F,
+) -> anyhow::Result<()>
+where
+    R: Read,
+    F: FnMut(u16, u32),
+{
+    let lngu = 255 - maxv as u64;
+    let mut run_length: Vec<u16> = Vec::new();
+    let mut number_of_read = 0;
+    for _ in 0..length {
+        let value = read_u8(reader)? as u16;
+        if value <= maxv && !run_length.is_empty() {
+            let (level, count) = expand_run_length(&run_length, maxv, lngu);
+            f(level, count);
+            run_length.clear();
+            // すべての格子点を展開した場合、残りは詰め物
+            number_of_read += count;
+            if number_of_points <= number_of_read {
+                return Ok(());
+            }
+        }
+        run_length.push(value);
+    }
+    if !run_length.is_empty() {
+        let (level, count) = expand_run_length(&run_length, maxv, lngu);
+        f(level, count);
+    }
+
+    Ok(())
+}
+
+/// リーダーから1バイト読み込む。
+fn read_u8<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
+    let mut buf = [0; 1];
+    if reader.read(&mut buf)? != 1 {
+        return Err(anyhow!("failed to read a u8 value"));
+    }
+
+    Ok(buf[0])
+}
+
+/// 1セットのランレングス符号化を、ランレングス値ごとに`Vec`を確保しながら展開する。
+fn expand_run_length(values: &[u16], maxv: u16, lngu: u64) -> (u16, u32) {
+    let values: Vec<u64> = values.iter().map(|v| *v as u64).collect();
+    let count: u64 = values[1..]
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| lngu.pow(i as u32) * (v - (maxv as u64 + 1)))
+        .sum();
+
+    (values[0] as u16, (count + 1) as u32)
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = decode_sample, decode_sample_in_memory, decode_sample_baseline
+}
+criterion_main!(benches);
@@ -0,0 +1,154 @@
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use time::PrimitiveDateTime;
+
+use crate::input::{open_stream, read_to_limit, InputReader, MAX_IN_MEMORY_SIZE};
+use crate::{Grib2Csv, ValidationPolicy};
+
+/// GRIB2報の先頭に記録されている文字列
+const GRIB_MAGIC: &[u8] = b"GRIB";
+
+/// tarアーカイブに格納されたGRIB2ファイル
+#[derive(Debug, Clone)]
+pub struct ArchiveMember {
+    /// アーカイブ内のパス
+    pub name: String,
+    /// 最初のGRIB2報の第1節に記録されている資料の参照時刻
+    pub referenced_at: PrimitiveDateTime,
+    /// ファイルに記録されているGRIB2報の数
+    pub number_of_messages: usize,
+}
+
+/// GRIB2ファイルを格納したtarアーカイブ
+///
+/// アーカイブ全体がgzip、bzip2またはxz形式で圧縮されている場合（`.tar.gz`など）は、展開しながら読み込む。
+/// アーカイブに格納されたファイルの内、GRIB2報で始まるファイル（圧縮されたファイルを含む）のみを扱い、
+/// それ以外のファイルは無視する。
+/// アーカイブは、操作するたびに先頭から順番に読み込むため、ファイル全体をメモリに保持しない。
+pub struct Grib2Archive {
+    /// tarアーカイブのパス
+    path: PathBuf,
+    /// ヘッダ出力フラグ
+    with_header: bool,
+    /// GRIB2報の検証方針
+    policy: ValidationPolicy,
+}
+
+impl Grib2Archive {
+    /// コンストラクタ
+    ///
+    /// # 引数
+    ///
+    /// * `path` - tarアーカイブのパス。
+    /// * `with_header` - ヘッダ出力フラグ。
+    pub fn new<P: AsRef<Path>>(path: P, with_header: bool) -> Self {
+        Self::with_policy(path, with_header, ValidationPolicy::default())
+    }
+
+    /// GRIB2報の検証方針を指定するコンストラクタ
+    ///
+    /// # 引数
+    ///
+    /// * `path` - tarアーカイブのパス。
+    /// * `with_header` - ヘッダ出力フラグ。
+    /// * `policy` - GRIB2報の検証方針。
+    pub fn with_policy<P: AsRef<Path>>(
+        path: P,
+        with_header: bool,
+        policy: ValidationPolicy,
+    ) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            with_header,
+            policy,
+        }
+    }
+
+    /// アーカイブに格納されているGRIB2ファイルを、格納されている順番で返却する。
+    pub fn members(&self) -> anyhow::Result<Vec<ArchiveMember>> {
+        let mut members = vec![];
+        self.for_each(|member, _| {
+            members.push(member.clone());
+            Ok(true)
+        })?;
+
+        Ok(members)
+    }
+
+    /// アーカイブに格納されている、指定されたパスのGRIB2ファイルを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `name` - アーカイブ内のGRIB2ファイルのパス。
+    pub fn member(&self, name: &str) -> anyhow::Result<Grib2Csv<InputReader>> {
+        let mut found = None;
+        self.for_each_member(
+            |member_name| member_name == name,
+            |_, grib2| {
+                found = Some(grib2);
+                Ok(false)
+            },
+        )?;
+
+        found.ok_or_else(|| anyhow!("the archive does not contain a grib2 file named {name}"))
+    }
+
+    /// アーカイブに格納されているGRIB2ファイルを、格納されている順番で読み込んで、関数に渡す。
+    ///
+    /// # 引数
+    ///
+    /// * `f` - GRIB2ファイルの情報と、GRIB2ファイルを読み込んだコンバーターを受け取る関数。
+    ///   以降のGRIB2ファイルを読み込む場合は`true`、読み込みを終了する場合は`false`を返却する。
+    pub fn for_each<F>(&self, f: F) -> anyhow::Result<()>
+    where
+        F: FnMut(&ArchiveMember, Grib2Csv<InputReader>) -> anyhow::Result<bool>,
+    {
+        self.for_each_member(|_| true, f)
+    }
+
+    /// アーカイブに格納されているファイルの内、パスが条件に一致するGRIB2ファイルを読み込んで、関数に渡す。
+    fn for_each_member<P, F>(&self, mut predicate: P, mut f: F) -> anyhow::Result<()>
+    where
+        P: FnMut(&str) -> bool,
+        F: FnMut(&ArchiveMember, Grib2Csv<InputReader>) -> anyhow::Result<bool>,
+    {
+        let mut archive = tar::Archive::new(open_stream(&self.path)?);
+        let entries = archive
+            .entries()
+            .map_err(|e| anyhow!("failed to read a tar archive: {e}"))?;
+        for entry in entries {
+            let mut entry = entry.map_err(|e| anyhow!("failed to read a tar archive: {e}"))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path()?.to_string_lossy().into_owned();
+            if !predicate(&name) {
+                continue;
+            }
+            // ファイルを読み込み、圧縮されている場合は展開
+            // ヘッダに記録されたファイルサイズは信頼できないため、事前に領域を確保せずに、
+            // 読み込むバイト数を制限
+            let bytes = read_to_limit(&mut entry, MAX_IN_MEMORY_SIZE)
+                .map_err(|e| anyhow!("failed to read {name} in the archive: {e}"))?;
+            let mut reader = InputReader::from_bytes(bytes)
+                .map_err(|e| anyhow!("failed to read {name} in the archive: {e}"))?;
+            if !reader.fill_buf()?.starts_with(GRIB_MAGIC) {
+                continue;
+            }
+            let grib2 = Grib2Csv::from_reader(reader, self.with_header, self.policy)
+                .map_err(|e| anyhow!("failed to read {name} in the archive: {e}"))?;
+            let member = ArchiveMember {
+                name,
+                referenced_at: grib2.metadata().section1.referenced_at,
+                number_of_messages: grib2.number_of_messages(),
+            };
+            if !f(&member, grib2)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,567 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use geojson::GeoJson;
+use wkt::Wkt;
+
+/// 格子と領域が交わるか判定するときに、格子を縮小する比率
+const CELL_SHRINK_FACTOR: f64 = 1.0 - 1e-6;
+
+/// 格子点を領域に含める条件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AreaMode {
+    /// 格子点（格子の中心）が領域に含まれる
+    #[default]
+    CellCenter,
+    /// 格子点を中心とする格子が、領域と交わる
+    CellIntersects,
+}
+
+/// 多角形またはマルチポリゴンで表現した領域
+///
+/// 多角形の頂点は、度単位の経度と緯度で表現する。
+/// 多角形の最初の環を外周、2番目以降の環を穴として扱い、穴に含まれる地点は領域に含めない。
+#[derive(Debug, Clone)]
+pub struct Area {
+    /// 領域を構成する多角形
+    polygons: Vec<Polygon>,
+}
+
+/// 穴を含む多角形
+#[derive(Debug, Clone)]
+struct Polygon {
+    /// 外周と穴の環（度単位の経度と緯度の頂点の列）
+    rings: Vec<Vec<(f64, f64)>>,
+    /// 外周を囲む矩形（最西端の経度、最南端の緯度、最東端の経度、最北端の緯度）
+    bounds: (f64, f64, f64, f64),
+}
+
+impl Area {
+    /// GeoJSONまたはWKT形式の文字列から、領域を読み込む。
+    ///
+    /// 文字列が`{`で始まる場合はGeoJSON形式、それ以外の場合はWKT形式として読み込む。
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        if text.trim_start().starts_with('{') {
+            Self::from_geojson(text)
+        } else {
+            Self::from_wkt(text)
+        }
+    }
+
+    /// GeoJSONまたはWKT形式で領域を記録したファイルを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 領域を記録したファイルのパス。
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+
+        Self::parse(&text).map_err(|e| anyhow!("failed to read {}: {e}", path.display()))
+    }
+
+    /// GeoJSON形式の文字列から、領域を読み込む。
+    ///
+    /// ジオメトリ、フィーチャーまたはフィーチャー・コレクションに含まれる、すべてのポリゴンとマルチポリゴンを
+    /// 合わせた領域を返却する。
+    pub fn from_geojson(text: &str) -> anyhow::Result<Self> {
+        let geojson =
+            GeoJson::from_str(text).map_err(|e| anyhow!("failed to parse geojson: {e}"))?;
+        let geometries: Vec<&geojson::Geometry> = match &geojson {
+            GeoJson::Geometry(geometry) => vec![geometry],
+            GeoJson::Feature(feature) => feature.geometry.iter().collect(),
+            GeoJson::FeatureCollection(collection) => collection
+                .features
+                .iter()
+                .filter_map(|feature| feature.geometry.as_ref())
+                .collect(),
+        };
+        let mut polygons = vec![];
+        for geometry in geometries {
+            push_geojson_polygons(&geometry.value, &mut polygons)?;
+        }
+
+        Self::new(polygons)
+    }
+
+    /// GeoJSON形式のジオメトリから、領域を読み込む。
+    pub(crate) fn from_geojson_geometry(geometry: &geojson::Geometry) -> anyhow::Result<Self> {
+        let mut polygons = vec![];
+        push_geojson_polygons(&geometry.value, &mut polygons)?;
+
+        Self::new(polygons)
+    }
+
+    /// WKT形式の文字列から、領域を読み込む。
+    ///
+    /// `POLYGON`、`MULTIPOLYGON`、またはそれらを含む`GEOMETRYCOLLECTION`を読み込む。
+    pub fn from_wkt(text: &str) -> anyhow::Result<Self> {
+        let wkt =
+            Wkt::<f64>::from_str(text.trim()).map_err(|e| anyhow!("failed to parse wkt: {e}"))?;
+        let mut polygons = vec![];
+        push_wkt_polygons(&wkt, &mut polygons)?;
+
+        Self::new(polygons)
+    }
+
+    /// 多角形の環の列から、領域を作成する。
+    fn new(polygons: Vec<Vec<Vec<(f64, f64)>>>) -> anyhow::Result<Self> {
+        if polygons.is_empty() {
+            return Err(anyhow!("the area does not contain any polygon"));
+        }
+        let polygons = polygons
+            .into_iter()
+            .map(Polygon::new)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self { polygons })
+    }
+
+    /// 経度と緯度（度）の地点が、領域に含まれるか確認する。
+    pub fn contains(&self, longitude: f64, latitude: f64) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| polygon.contains(longitude, latitude))
+    }
+
+    /// 経度と緯度（度）の頂点で表現した凸四角形が、領域と交わるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `corners` - 凸四角形の頂点（時計回りまたは反時計回り）。
+    pub fn intersects(&self, corners: &[(f64, f64); 4]) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| polygon.intersects(corners))
+    }
+
+    /// 経度と緯度（度）の頂点で表現した凸四角形のうち、領域と重なる部分の面積の比率を返却する。
+    ///
+    /// 面積は、経度と緯度を直交座標とみなして計算する。
+    ///
+    /// # 引数
+    ///
+    /// * `corners` - 凸四角形の頂点（時計回りまたは反時計回り）。
+    pub(crate) fn overlap_ratio(&self, corners: &[(f64, f64); 4]) -> f64 {
+        let cell_area = signed_area(corners).abs();
+        if cell_area == 0.0 {
+            return 0.0;
+        }
+        let overlap: f64 = self
+            .polygons
+            .iter()
+            .filter(|polygon| polygon.overlaps_bounds(corners))
+            .map(|polygon| {
+                // 外周と重なる部分の面積から、穴と重なる部分の面積を除く
+                let mut areas = polygon.rings.iter().map(|ring| clipped_area(ring, corners));
+                let exterior = areas.next().unwrap_or_default();
+                exterior - areas.sum::<f64>()
+            })
+            .sum();
+
+        (overlap / cell_area).clamp(0.0, 1.0)
+    }
+
+    /// 領域を構成する多角形の外周を囲む矩形（最西端の経度、最南端の緯度、最東端の経度、最北端の緯度）を返却する。
+    pub(crate) fn bounds(&self) -> impl Iterator<Item = (f64, f64, f64, f64)> + '_ {
+        self.polygons.iter().map(|polygon| polygon.bounds)
+    }
+
+    /// 格子点または格子点を中心とする格子が、領域に含まれるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `mode` - 格子点を領域に含める条件。
+    /// * `longitude` - 格子点の経度（10^6度単位）。
+    /// * `latitude` - 格子点の緯度（10^6度単位）。
+    /// * `corners` - 格子点を中心とする格子の頂点を返却する関数。
+    pub(crate) fn includes<F>(
+        &self,
+        mode: AreaMode,
+        longitude: i32,
+        latitude: i32,
+        corners: F,
+    ) -> bool
+    where
+        F: FnOnce() -> [(f64, f64); 4],
+    {
+        match mode {
+            AreaMode::CellCenter => self.contains(
+                longitude as f64 / 1_000_000f64,
+                latitude as f64 / 1_000_000f64,
+            ),
+            AreaMode::CellIntersects => {
+                // 格子と領域が辺や頂点で接するだけの場合に、座標の丸め誤差で判定が変わらないように、
+                // 格子をわずかに縮小してから判定する
+                let corners = corners();
+                let center = corners.iter().fold((0.0, 0.0), |(x, y), corner| {
+                    (x + corner.0 / 4.0, y + corner.1 / 4.0)
+                });
+                self.intersects(&corners.map(|(x, y)| {
+                    (
+                        center.0 + (x - center.0) * CELL_SHRINK_FACTOR,
+                        center.1 + (y - center.1) * CELL_SHRINK_FACTOR,
+                    )
+                }))
+            }
+        }
+    }
+}
+
+impl Polygon {
+    fn new(rings: Vec<Vec<(f64, f64)>>) -> anyhow::Result<Self> {
+        if rings.iter().any(|ring| ring.len() < 3) {
+            return Err(anyhow!(
+                "a ring of a polygon must have at least 3 positions"
+            ));
+        }
+        let Some(exterior) = rings.first() else {
+            return Err(anyhow!("a polygon must have an exterior ring"));
+        };
+        let bounds = exterior.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(west, south, east, north), &(x, y)| {
+                (west.min(x), south.min(y), east.max(x), north.max(y))
+            },
+        );
+
+        Ok(Self { rings, bounds })
+    }
+
+    /// 地点が、外周に含まれ、穴に含まれないか確認する。
+    ///
+    /// 地点から東に伸ばした半直線と交わる辺の数が奇数の場合に、地点が多角形に含まれると判定する。
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let (west, south, east, north) = self.bounds;
+        if x < west || east < x || y < south || north < y {
+            return false;
+        }
+        let mut inside = false;
+        for ring in &self.rings {
+            for (&(x1, y1), &(x2, y2)) in edges(ring) {
+                if (y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+
+    /// 凸四角形が多角形と交わるか確認する。
+    ///
+    /// 凸四角形の頂点が多角形に含まれる場合、多角形の頂点が凸四角形に含まれる場合、
+    /// または凸四角形の辺と多角形の辺が交わる場合に、交わると判定する。
+    fn intersects(&self, corners: &[(f64, f64); 4]) -> bool {
+        if !self.overlaps_bounds(corners) {
+            return false;
+        }
+        if corners.iter().any(|&(x, y)| self.contains(x, y)) {
+            return true;
+        }
+        let cell_edges: Vec<_> = edges(corners).collect();
+        self.rings.iter().any(|ring| {
+            ring.iter().any(|&point| contains_in_convex(corners, point))
+                || edges(ring).any(|(a, b)| {
+                    cell_edges
+                        .iter()
+                        .any(|&(c, d)| segments_intersect(*a, *b, *c, *d))
+                })
+        })
+    }
+
+    /// 凸四角形を囲む矩形が、外周を囲む矩形と重なるか確認する。
+    fn overlaps_bounds(&self, corners: &[(f64, f64); 4]) -> bool {
+        let (west, south, east, north) = self.bounds;
+        let (cell_west, cell_south, cell_east, cell_north) = corners.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(w, s, e, n), &(x, y)| (w.min(x), s.min(y), e.max(x), n.max(y)),
+        );
+
+        west <= cell_east && cell_west <= east && south <= cell_north && cell_south <= north
+    }
+}
+
+/// 環を凸多角形で切り取った多角形の面積を返却する。
+///
+/// Sutherland-Hodgmanのアルゴリズムで、凸多角形の辺ごとに、辺の内側にある環の部分を残す。
+/// 環が凹多角形の場合、切り取った多角形は凸多角形の辺上で折り返す辺を含むが、面積は正しく計算できる。
+fn clipped_area(ring: &[(f64, f64)], convex: &[(f64, f64)]) -> f64 {
+    let orientation = signed_area(convex).signum();
+    let mut clipped = ring.to_vec();
+    for (&a, &b) in edges(convex) {
+        if clipped.is_empty() {
+            break;
+        }
+        let inside = |p| 0.0 <= cross(a, b, p) * orientation;
+        let input = std::mem::take(&mut clipped);
+        for (&p, &q) in edges(&input) {
+            match (inside(p), inside(q)) {
+                (true, true) => clipped.push(q),
+                (true, false) => clipped.push(line_intersection(a, b, p, q)),
+                (false, true) => {
+                    clipped.push(line_intersection(a, b, p, q));
+                    clipped.push(q);
+                }
+                (false, false) => {}
+            }
+        }
+    }
+
+    signed_area(&clipped).abs()
+}
+
+/// 環の符号付き面積（反時計回りの場合は正）を返却する。
+fn signed_area(ring: &[(f64, f64)]) -> f64 {
+    let Some(&origin) = ring.first() else {
+        return 0.0;
+    };
+
+    edges(ring).map(|(&p, &q)| cross(origin, p, q)).sum::<f64>() / 2.0
+}
+
+/// 直線abと線分pqの交点を返却する。
+fn line_intersection(a: (f64, f64), b: (f64, f64), p: (f64, f64), q: (f64, f64)) -> (f64, f64) {
+    let (dp, dq) = (cross(a, b, p), cross(a, b, q));
+    let t = dp / (dp - dq);
+
+    (p.0 + t * (q.0 - p.0), p.1 + t * (q.1 - p.1))
+}
+
+/// 環の辺（頂点の組）を返却するイテレーターを返却する。
+///
+/// 最後の頂点と最初の頂点を結ぶ辺を含む。
+fn edges(ring: &[(f64, f64)]) -> impl Iterator<Item = (&(f64, f64), &(f64, f64))> {
+    ring.iter().zip(ring.iter().cycle().skip(1))
+}
+
+/// 地点が、凸多角形に含まれるか（辺上を含む）確認する。
+fn contains_in_convex(polygon: &[(f64, f64)], point: (f64, f64)) -> bool {
+    let mut sign = 0.0;
+    for (&a, &b) in edges(polygon) {
+        let cross = cross(a, b, point);
+        if cross != 0.0 {
+            if sign * cross < 0.0 {
+                return false;
+            }
+            sign = cross;
+        }
+    }
+
+    true
+}
+
+/// 線分abと線分cdが交わるか（端点での接触を含む）確認する。
+fn segments_intersect(a: (f64, f64), b: (f64, f64), c: (f64, f64), d: (f64, f64)) -> bool {
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if ((0.0 < d1 && d2 < 0.0) || (d1 < 0.0 && 0.0 < d2))
+        && ((0.0 < d3 && d4 < 0.0) || (d3 < 0.0 && 0.0 < d4))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(c, d, a))
+        || (d2 == 0.0 && on_segment(c, d, b))
+        || (d3 == 0.0 && on_segment(a, b, c))
+        || (d4 == 0.0 && on_segment(a, b, d))
+}
+
+/// ベクトルabとベクトルacの外積を返却する。
+fn cross(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// 線分ab上の点と同一直線上にある点pが、線分abの範囲に含まれるか確認する。
+fn on_segment(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> bool {
+    a.0.min(b.0) <= p.0 && p.0 <= a.0.max(b.0) && a.1.min(b.1) <= p.1 && p.1 <= a.1.max(b.1)
+}
+
+/// GeoJSON形式のジオメトリに含まれる多角形を追加する。
+fn push_geojson_polygons(
+    value: &geojson::Value,
+    polygons: &mut Vec<Vec<Vec<(f64, f64)>>>,
+) -> anyhow::Result<()> {
+    let polygon = |rings: &geojson::PolygonType| -> anyhow::Result<Vec<Vec<(f64, f64)>>> {
+        rings
+            .iter()
+            .map(|ring| {
+                ring.iter()
+                    .map(|position| match position[..] {
+                        [x, y, ..] => Ok((x, y)),
+                        _ => Err(anyhow!("a position must have longitude and latitude")),
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+    match value {
+        geojson::Value::Polygon(rings) => polygons.push(polygon(rings)?),
+        geojson::Value::MultiPolygon(members) => {
+            for rings in members {
+                polygons.push(polygon(rings)?);
+            }
+        }
+        geojson::Value::GeometryCollection(geometries) => {
+            for geometry in geometries {
+                push_geojson_polygons(&geometry.value, polygons)?;
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "the geometry must be a polygon or a multipolygon ({})",
+                value.type_name()
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// WKT形式のジオメトリに含まれる多角形を追加する。
+fn push_wkt_polygons(
+    wkt: &Wkt<f64>,
+    polygons: &mut Vec<Vec<Vec<(f64, f64)>>>,
+) -> anyhow::Result<()> {
+    let polygon = |polygon: &wkt::types::Polygon<f64>| -> Vec<Vec<(f64, f64)>> {
+        polygon
+            .0
+            .iter()
+            .map(|ring| ring.0.iter().map(|coord| (coord.x, coord.y)).collect())
+            .collect()
+    };
+    match wkt {
+        Wkt::Polygon(rings) => polygons.push(polygon(rings)),
+        Wkt::MultiPolygon(members) => {
+            for rings in &members.0 {
+                polygons.push(polygon(rings));
+            }
+        }
+        Wkt::GeometryCollection(collection) => {
+            for geometry in &collection.0 {
+                push_wkt_polygons(geometry, polygons)?;
+            }
+        }
+        _ => return Err(anyhow!("the geometry must be a polygon or a multipolygon")),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 経度130度から134度、緯度30度から34度の正方形から、経度131度から133度、緯度31度から33度の穴を除いた領域
+    const SQUARE_WITH_HOLE: &str = "POLYGON ((130 30, 134 30, 134 34, 130 34, 130 30), (131 31, 133 31, 133 33, 131 33, 131 31))";
+
+    /// 経度と緯度（度）を中心とする、一辺が`size`度の格子の頂点を返却する。
+    fn cell(longitude: f64, latitude: f64, size: f64) -> [(f64, f64); 4] {
+        let half = size / 2.0;
+        [
+            (longitude - half, latitude - half),
+            (longitude + half, latitude - half),
+            (longitude + half, latitude + half),
+            (longitude - half, latitude + half),
+        ]
+    }
+
+    #[test]
+    fn polygon_with_hole_contains_points() {
+        let area = Area::parse(SQUARE_WITH_HOLE).unwrap();
+        assert!(area.contains(130.5, 30.5));
+        assert!(area.contains(133.5, 32.0));
+        // 穴と外周の外側
+        assert!(!area.contains(132.0, 32.0));
+        assert!(!area.contains(135.0, 32.0));
+        assert!(!area.contains(132.0, 29.0));
+    }
+
+    #[test]
+    fn cell_intersects_polygon() {
+        let area = Area::parse(SQUARE_WITH_HOLE).unwrap();
+        // 格子の中心は領域の外側だが、格子は外周と交わる
+        assert!(!area.contains(129.9, 32.0));
+        assert!(area.intersects(&cell(129.9, 32.0, 0.5)));
+        // 格子の中心は穴の中にあるが、格子は穴の境界と交わる
+        assert!(area.intersects(&cell(131.1, 32.0, 0.5)));
+        // 格子全体が穴に含まれる
+        assert!(!area.intersects(&cell(132.0, 32.0, 0.5)));
+        // 格子全体が外周の外側にある
+        assert!(!area.intersects(&cell(135.0, 32.0, 0.5)));
+        // 領域全体が格子に含まれる
+        assert!(area.intersects(&cell(132.0, 32.0, 10.0)));
+        // 外周の辺と接するだけの格子は、領域と交わらない
+        let includes = |longitude: f64, latitude: f64| {
+            area.includes(
+                AreaMode::CellIntersects,
+                (longitude * 1_000_000f64) as i32,
+                (latitude * 1_000_000f64) as i32,
+                || cell(longitude, latitude, 0.5),
+            )
+        };
+        assert!(!includes(129.75, 32.0));
+        assert!(includes(129.8, 32.0));
+        assert!(!includes(132.0, 32.0));
+        assert!(includes(131.2, 32.0));
+    }
+
+    #[test]
+    fn overlap_ratio_of_cell() {
+        let area = Area::parse(SQUARE_WITH_HOLE).unwrap();
+        let ratio = |longitude: f64, latitude: f64, size: f64| {
+            area.overlap_ratio(&cell(longitude, latitude, size))
+        };
+        assert!((ratio(130.0, 32.0, 0.5) - 0.5).abs() < 1e-9);
+        assert!((ratio(133.9, 32.0, 0.5) - 0.7).abs() < 1e-9);
+        // 格子の4分の1が穴と重なる
+        assert!((ratio(131.0, 31.0, 0.5) - 0.75).abs() < 1e-9);
+        assert!((ratio(130.5, 30.5, 0.5) - 1.0).abs() < 1e-9);
+        assert_eq!(ratio(132.0, 32.0, 0.5), 0.0);
+        assert_eq!(ratio(135.0, 32.0, 0.5), 0.0);
+        // 領域全体が格子に含まれる（16 - 4 = 12平方度）
+        assert!((ratio(132.0, 32.0, 10.0) - 0.12).abs() < 1e-9);
+
+        // 凹多角形の窪みと重なる格子
+        let area = Area::parse("POLYGON ((0 0, 4 0, 4 4, 2 1, 0 4, 0 0))").unwrap();
+        assert!((area.overlap_ratio(&cell(2.0, 3.0, 2.0)) - 1.0 / 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn read_geojson_and_wkt() {
+        let geojson = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {"id": "a"},
+                    "geometry": {"type": "Polygon", "coordinates": [[[130, 30], [131, 30], [131, 31], [130, 30]]]}
+                },
+                {
+                    "type": "Feature",
+                    "properties": {"id": "b"},
+                    "geometry": {
+                        "type": "MultiPolygon",
+                        "coordinates": [[[[140, 40], [141, 40], [141, 41], [140, 41], [140, 40]]]]
+                    }
+                }
+            ]
+        }"#;
+        let area = Area::parse(geojson).unwrap();
+        assert!(area.contains(130.9, 30.1));
+        assert!(!area.contains(130.1, 30.9));
+        assert!(area.contains(140.5, 40.5));
+
+        let area = Area::parse("MULTIPOLYGON (((130 30, 131 30, 131 31, 130 30)), ((140 40, 141 40, 141 41, 140 41, 140 40)))").unwrap();
+        assert!(area.contains(130.9, 30.1));
+        assert!(area.contains(140.5, 40.5));
+
+        // 多角形以外のジオメトリや、頂点が足りない多角形
+        assert!(Area::parse(r#"{"type": "Point", "coordinates": [130, 30]}"#).is_err());
+        assert!(Area::parse("LINESTRING (130 30, 131 31)").is_err());
+        assert!(Area::parse("POLYGON ((130 30, 131 31))").is_err());
+        assert!(Area::parse(r#"{"type": "FeatureCollection", "features": []}"#).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::anyhow;
+
+/// オクテット列から、最上位ビットから順番に任意のビット数の値を読み込むリーダー
+///
+/// 第7節の資料値は、あらかじめメモリに読み込んだオクテット列から読み込む。
+pub(crate) struct BitReader<B: AsRef<[u8]>> {
+    bytes: B,
+    /// 次に読み込むオクテットの位置
+    position: usize,
+    /// 読み込んだが、まだ値として返却していないビット
+    buffer: u64,
+    /// `buffer`に残っているビット数
+    bits: u32,
+}
+
+impl<B: AsRef<[u8]>> BitReader<B> {
+    pub(crate) fn new(bytes: B) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            buffer: 0,
+            bits: 0,
+        }
+    }
+
+    /// 指定されたビット数の値を読み込んで、返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `nbit` - 読み込むビット数（0以上32以下）。
+    #[inline]
+    pub(crate) fn read(&mut self, nbit: u32) -> anyhow::Result<u32> {
+        if 32 < nbit {
+            return Err(anyhow!("a bit width must be less than or equal to 32"));
+        }
+        let bytes = self.bytes.as_ref();
+        while self.bits < nbit {
+            let Some(&byte) = bytes.get(self.position) else {
+                return Err(anyhow!("failed to read {nbit} bits"));
+            };
+            self.position += 1;
+            self.buffer = (self.buffer << 8) | byte as u64;
+            self.bits += 8;
+        }
+        self.bits -= nbit;
+        let value = (self.buffer >> self.bits) & ((1u64 << nbit) - 1);
+        self.buffer &= (1u64 << self.bits) - 1;
+
+        Ok(value as u32)
+    }
+
+    /// 読み込んだオクテットの残りのビットを読み飛ばして、次のオクテットの境界に移動する。
+    pub(crate) fn align(&mut self) {
+        self.buffer = 0;
+        self.bits = 0;
+    }
+
+    /// 次のオクテットの境界の位置（オクテット列の先頭からのオクテット数）を返却する。
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 読み込んだオクテットの残りのビットを読み飛ばして、指定された位置のオクテットに移動する。
+    ///
+    /// # 引数
+    ///
+    /// * `position` - 移動先のオクテット列の先頭からのオクテット数。
+    pub(crate) fn seek(&mut self, position: usize) {
+        self.position = position;
+        self.align();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_values_of_various_bit_widths() {
+        // 0b1010_1100, 0b0011_1111, 0b0000_0001
+        let bytes = [0xac, 0x3f, 0x01];
+        let mut reader = BitReader::new(&bytes[..]);
+        assert_eq!(reader.read(1).unwrap(), 0b1);
+        assert_eq!(reader.read(3).unwrap(), 0b010);
+        assert_eq!(reader.read(6).unwrap(), 0b11_0000);
+        assert_eq!(reader.read(0).unwrap(), 0);
+        assert_eq!(reader.read(14).unwrap(), 0b11_1111_0000_0001);
+        assert!(reader.read(1).is_err());
+    }
+
+    #[test]
+    fn align_to_next_octet() {
+        let bytes = [0xac, 0x3f];
+        let mut reader = BitReader::new(&bytes[..]);
+        assert_eq!(reader.read(3).unwrap(), 0b101);
+        reader.align();
+        assert_eq!(reader.read(8).unwrap(), 0x3f);
+    }
+
+    #[test]
+    fn seek_to_octet() {
+        let bytes = [0xac, 0x3f, 0x01];
+        let mut reader = BitReader::new(&bytes[..]);
+        assert_eq!(reader.read(3).unwrap(), 0b101);
+        assert_eq!(reader.position(), 1);
+        reader.seek(2);
+        assert_eq!(reader.read(8).unwrap(), 0x01);
+        reader.seek(0);
+        assert_eq!(reader.read(4).unwrap(), 0xa);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::anyhow;
+
+/// 第6節のビットマップに従って、資料値を格子点に割り当てるエキスパンダー
+///
+/// 第7節には、ビットマップのビットが1の格子点の資料値のみが記録されている。
+/// ビットマップのビットが0の格子点は、欠測値として扱う。
+pub(crate) struct BitmapExpander<'a> {
+    /// ビットマップ
+    bitmap: &'a [u8],
+    /// 全格子点の数
+    number_of_points: u32,
+    /// 次に資料値を割り当てる格子点の、資料の並び順でのインデックス
+    position: u32,
+}
+
+impl<'a> BitmapExpander<'a> {
+    pub(crate) fn new(bitmap: &'a [u8], number_of_points: u32) -> Self {
+        Self {
+            bitmap,
+            number_of_points,
+            position: 0,
+        }
+    }
+
+    /// 連続する資料値を、ビットマップのビットが1の格子点に割り当てる。
+    ///
+    /// # 引数
+    ///
+    /// * `value` - 物理値。欠測値の場合は`None`。
+    /// * `count` - 物理値が連続する資料値の数。
+    /// * `f` - 格子点の物理値と、その物理値が連続する格子点の数を受け取る関数。
+    pub(crate) fn expand<F>(
+        &mut self,
+        value: Option<f64>,
+        count: u32,
+        f: &mut F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnMut(Option<f64>, u32) -> anyhow::Result<()>,
+    {
+        let mut remaining = count;
+        while 0 < remaining {
+            // ビットマップで除外された格子点
+            let masked = self.run(false, u32::MAX);
+            if 0 < masked {
+                f(None, masked)?;
+            }
+            if self.number_of_points <= self.position {
+                return Err(anyhow!(
+                    "the number of values exceeds the number of points in the bitmap"
+                ));
+            }
+            // 資料値を割り当てる格子点
+            let defined = self.run(true, remaining);
+            f(value, defined)?;
+            remaining -= defined;
+        }
+
+        Ok(())
+    }
+
+    /// 最後の資料値を割り当てた後に残っている、ビットマップで除外された格子点を欠測値として出力する。
+    ///
+    /// # 戻り値
+    ///
+    /// 全格子点の数。
+    pub(crate) fn finish<F>(mut self, f: &mut F) -> anyhow::Result<u32>
+    where
+        F: FnMut(Option<f64>, u32) -> anyhow::Result<()>,
+    {
+        let masked = self.run(false, u32::MAX);
+        if 0 < masked {
+            f(None, masked)?;
+        }
+        if self.position < self.number_of_points {
+            return Err(anyhow!(
+                "the number of values is less than the number of points in the bitmap"
+            ));
+        }
+
+        Ok(self.position)
+    }
+
+    /// 現在の格子点から、ビットが`bit`と等しい格子点が連続する数を、`limit`を上限として数えて、
+    /// その数だけ格子点を進める。
+    fn run(&mut self, bit: bool, limit: u32) -> u32 {
+        let start = self.position;
+        while self.position < self.number_of_points
+            && self.position - start < limit
+            && self.is_defined(self.position) == bit
+        {
+            self.position += 1;
+        }
+
+        self.position - start
+    }
+
+    /// 格子点に資料値が存在するか確認する。
+    fn is_defined(&self, index: u32) -> bool {
+        is_defined(self.bitmap, index)
+    }
+}
+
+/// ビットマップのビットが1で、資料の並び順で`index`番目の格子点に資料値が存在するか確認する。
+pub(crate) fn is_defined(bitmap: &[u8], index: u32) -> bool {
+    bitmap[(index / 8) as usize] & (0x80 >> (index % 8)) != 0
+}
+
+/// ビットマップのビットが1の格子点の数を返却する。
+pub(crate) fn count_defined_points(bitmap: &[u8], number_of_points: u32) -> u32 {
+    let expander = BitmapExpander::new(bitmap, number_of_points);
+    (0..number_of_points)
+        .filter(|&index| expander.is_defined(index))
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_values_to_defined_points() {
+        // 0b0110_1100, 0b1000_0000（最後の2ビットは詰め物）
+        let bitmap = [0x6c, 0x80];
+        assert_eq!(count_defined_points(&bitmap, 10), 5);
+
+        let mut expanded = Vec::new();
+        let mut f = |value: Option<f64>, count: u32| {
+            expanded.push((value, count));
+            Ok(())
+        };
+        let mut expander = BitmapExpander::new(&bitmap, 10);
+        expander.expand(Some(1.0), 3, &mut f).unwrap();
+        expander.expand(None, 1, &mut f).unwrap();
+        expander.expand(Some(2.0), 1, &mut f).unwrap();
+        assert_eq!(expander.finish(&mut f).unwrap(), 10);
+        assert_eq!(
+            expanded,
+            vec![
+                (None, 1),
+                (Some(1.0), 2),
+                (None, 1),
+                (Some(1.0), 1),
+                (None, 1),
+                (None, 2),
+                (Some(2.0), 1),
+                (None, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_values_inconsistent_with_bitmap() {
+        let bitmap = [0x6c, 0x80];
+        let mut f = |_: Option<f64>, _: u32| Ok(());
+        // 資料値がビットマップのビットが1の格子点の数より多い
+        let mut expander = BitmapExpander::new(&bitmap, 10);
+        assert!(expander.expand(Some(1.0), 6, &mut f).is_err());
+        // 資料値がビットマップのビットが1の格子点の数より少ない
+        let mut expander = BitmapExpander::new(&bitmap, 10);
+        expander.expand(Some(1.0), 4, &mut f).unwrap();
+        assert!(expander.finish(&mut f).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::io::Read;
+
+use anyhow::anyhow;
+
+use crate::bit_reader::BitReader;
+use crate::{ComplexPacking, SpatialDifferencing};
+
+/// 欠測値の管理: 欠測値を含まない
+const MISSING_VALUE_MANAGEMENT_NONE: u8 = 0;
+/// 欠測値の管理: 第一欠測値を含む
+const MISSING_VALUE_MANAGEMENT_PRIMARY: u8 = 1;
+/// 欠測値の管理: 第一欠測値と第二欠測値を含む
+const MISSING_VALUE_MANAGEMENT_PRIMARY_AND_SECONDARY: u8 = 2;
+/// グループの幅の最大値
+const MAX_GROUP_WIDTH: u32 = 32;
+
+/// 複合圧縮のグループ
+struct Group {
+    /// グループの参照値
+    reference: i64,
+    /// グループの幅
+    width: u32,
+    /// グループの長さ
+    length: u64,
+}
+
+/// 第7節の複合圧縮（資料表現テンプレート5.2及び5.3）された資料値を、1つずつ順番に展開するデコーダー
+///
+/// 資料値は、空間差分の追加記述子（テンプレート5.3の場合）、グループの参照値、グループの幅、
+/// グループの長さ及び圧縮された値の順番で記録されている。
+/// グループの参照値、グループの幅及びグループの長さの末尾は、オクテットの境界に合わせて0で詰められている。
+///
+/// デコーダーの作成時にグループの情報のみを読み込み、圧縮された値は要求されるたびに展開する。
+/// 空間差分は、直前の1つまたは2つの値から元に戻すため、展開した物理値を保持しない。
+pub(crate) struct ComplexPackingDecoder {
+    /// 複合圧縮の資料表現
+    packing: ComplexPacking,
+    /// 空間差分の追加記述子
+    descriptors: Option<ExtraDescriptors>,
+    /// グループ
+    groups: Vec<Group>,
+    /// 圧縮された値の開始位置（第7節の資料値の先頭からのオクテット数）。読み込みを開始した場合は`None`
+    offset: Option<usize>,
+    /// 次に展開するグループのインデックス
+    next_group: usize,
+    /// 展開中のグループの、展開していない値の数
+    remaining: u64,
+    /// 空間差分を元に戻した値の数
+    restored: usize,
+    /// 最後に空間差分を元に戻した値
+    last: i64,
+    /// 最後から2番目に空間差分を元に戻した値
+    penultimate: i64,
+}
+
+impl ComplexPackingDecoder {
+    /// 第7節の資料値から、空間差分の追加記述子とグループの情報を読み込んで、デコーダーを作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `data` - 第7節の資料値。
+    /// * `packing` - 複合圧縮の資料表現。
+    /// * `number_of_values` - 資料値の数。
+    pub(crate) fn new(
+        data: &[u8],
+        packing: &ComplexPacking,
+        number_of_values: u32,
+    ) -> anyhow::Result<Self> {
+        let mut reader = data;
+        let number_of_groups = packing.number_of_groups as usize;
+        // 空間差分の追加記述子
+        let descriptors = match &packing.spatial_differencing {
+            Some(differencing) => Some(read_extra_descriptors(&mut reader, differencing)?),
+            None => None,
+        };
+        let descriptors_length = data.len() - reader.len();
+        // グループの参照値
+        let reference_bits = packing.simple_packing.bits_per_value as u32;
+        let mut bits = BitReader::new(reader);
+        let mut groups = Vec::with_capacity(number_of_groups);
+        for _ in 0..number_of_groups {
+            groups.push(Group {
+                reference: bits.read(reference_bits)? as i64,
+                width: 0,
+                length: 0,
+            });
+        }
+        // グループの幅
+        bits.align();
+        for group in groups.iter_mut() {
+            let width = packing.group_width_reference as u32
+                + bits.read(packing.group_width_bits as u32)?;
+            if MAX_GROUP_WIDTH < width {
+                return Err(anyhow!(
+                    "a group width is greater than {MAX_GROUP_WIDTH} (width:{width})"
+                ));
+            }
+            group.width = width;
+        }
+        // グループの長さ
+        bits.align();
+        for group in groups.iter_mut() {
+            let scaled = bits.read(packing.group_length_bits as u32)? as u64;
+            group.length = packing.group_length_reference as u64
+                + scaled * packing.group_length_increment as u64;
+        }
+        // 最後のグループの長さは、真の長さで置き換える
+        if let Some(last) = groups.last_mut() {
+            last.length = packing.last_group_length as u64;
+        }
+        let total: u64 = groups.iter().map(|group| group.length).sum();
+        if total != number_of_values as u64 {
+            return Err(anyhow!(
+                "the sum of group lengths is different from the number of values (sum:{total}, values:{number_of_values})"
+            ));
+        }
+        bits.align();
+
+        Ok(Self {
+            packing: packing.clone(),
+            descriptors,
+            groups,
+            offset: Some(descriptors_length + bits.position()),
+            next_group: 0,
+            remaining: 0,
+            restored: 0,
+            last: 0,
+            penultimate: 0,
+        })
+    }
+
+    /// 次の資料値を展開して、物理値を返却する。
+    ///
+    /// 欠測値の場合、物理値は`None`。すべての資料値を展開した場合は`None`を返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `bits` - デコーダーを作成した第7節の資料値を読み込むリーダー。
+    pub(crate) fn next<B: AsRef<[u8]>>(
+        &mut self,
+        bits: &mut BitReader<B>,
+    ) -> anyhow::Result<Option<Option<f64>>> {
+        // 最初の資料値を展開する場合は、圧縮された値の開始位置に移動
+        if let Some(offset) = self.offset.take() {
+            bits.seek(offset);
+        }
+        while self.remaining == 0 {
+            let Some(group) = self.groups.get(self.next_group) else {
+                return Ok(None);
+            };
+            self.remaining = group.length;
+            self.next_group += 1;
+        }
+        self.remaining -= 1;
+        let group = &self.groups[self.next_group - 1];
+        let (reference, width) = (group.reference, group.width);
+        let packed = bits.read(width)?;
+        let missing = if width == 0 {
+            is_missing(
+                self.packing.missing_value_management,
+                reference as u32,
+                self.packing.simple_packing.bits_per_value as u32,
+            )
+        } else {
+            is_missing(self.packing.missing_value_management, packed, width)
+        };
+        if missing {
+            return Ok(Some(None));
+        }
+        let value = self.undo_spatial_differencing(reference + packed as i64);
+
+        Ok(Some(Some(self.packing.simple_packing.value(value))))
+    }
+
+    /// 空間差分を元に戻す。
+    ///
+    /// 空間差分は欠測値ではない値に対して適用されているため、欠測値ではない値のみを渡す。
+    /// 欠測値ではない最初の値（2次の空間差分の場合は最初の2つの値）は、追加記述子に記録された値で置き換える。
+    fn undo_spatial_differencing(&mut self, value: i64) -> i64 {
+        let Some(descriptors) = &self.descriptors else {
+            return value;
+        };
+        let order = descriptors.first_values.len();
+        let restored = if self.restored < order {
+            descriptors.first_values[self.restored]
+        } else if order == 1 {
+            value + descriptors.minimum + self.last
+        } else {
+            value + descriptors.minimum + 2 * self.last - self.penultimate
+        };
+        self.restored += 1;
+        self.penultimate = self.last;
+        self.last = restored;
+
+        restored
+    }
+}
+
+/// 空間差分の追加記述子
+struct ExtraDescriptors {
+    /// 元の資料値の最初の値（2次の空間差分の場合は最初の2つの値）
+    first_values: Vec<i64>,
+    /// 空間差分の最小値
+    minimum: i64,
+}
+
+/// 空間差分の追加記述子を読み込んで、返却する。
+///
+/// 追加記述子は、最上位ビットを符号とした符号付き整数として記録されている。
+fn read_extra_descriptors<R: Read>(
+    reader: &mut R,
+    differencing: &SpatialDifferencing,
+) -> anyhow::Result<ExtraDescriptors> {
+    let mut read_descriptor = || -> anyhow::Result<i64> {
+        let mut buf = vec![0; differencing.number_of_octets as usize];
+        reader
+            .read_exact(&mut buf)
+            .map_err(|_| anyhow!("failed to read an extra descriptor of spatial differencing"))?;
+        let value = buf.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        let sign_bit = 1u64 << (buf.len() * 8 - 1);
+        let magnitude = (value & (sign_bit - 1)) as i64;
+        Ok(if value & sign_bit == 0 {
+            magnitude
+        } else {
+            -magnitude
+        })
+    };
+    let mut first_values = Vec::new();
+    for _ in 0..differencing.order {
+        first_values.push(read_descriptor()?);
+    }
+    let minimum = read_descriptor()?;
+
+    Ok(ExtraDescriptors {
+        first_values,
+        minimum,
+    })
+}
+
+/// 圧縮された値が欠測値であるか確認する。
+///
+/// 欠測値は、値を記録するビット数のすべてのビットが1の場合に第一欠測値、
+/// 最下位ビットのみが0の場合に第二欠測値である。
+fn is_missing(missing_value_management: u8, packed: u32, nbit: u32) -> bool {
+    if nbit == 0 {
+        return false;
+    }
+    let all_ones = (((1u64 << nbit) - 1) & u32::MAX as u64) as u32;
+    match missing_value_management {
+        MISSING_VALUE_MANAGEMENT_PRIMARY => packed == all_ones,
+        MISSING_VALUE_MANAGEMENT_PRIMARY_AND_SECONDARY => {
+            packed == all_ones || packed == all_ones - 1
+        }
+        _ => false,
+    }
+}
+
+/// 欠測値の管理が、対応している値であるか確認する。
+pub(crate) fn validate_missing_value_management(value: u8) -> anyhow::Result<u8> {
+    match value {
+        MISSING_VALUE_MANAGEMENT_NONE
+        | MISSING_VALUE_MANAGEMENT_PRIMARY
+        | MISSING_VALUE_MANAGEMENT_PRIMARY_AND_SECONDARY => Ok(value),
+        _ => Err(anyhow!(
+            "a missing value management {value} is not supported"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimplePacking;
+
+    /// 値とビット数の組を、最上位ビットから詰めたオクテット列に変換する。
+    fn pack_bits(values: &[(u32, u32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let (mut buffer, mut bits) = (0u64, 0u32);
+        for &(value, nbit) in values {
+            buffer = (buffer << nbit) | value as u64;
+            bits += nbit;
+            while 8 <= bits {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+        if 0 < bits {
+            bytes.push((buffer << (8 - bits)) as u8);
+        }
+
+        bytes
+    }
+
+    fn complex_packing(
+        missing_value_management: u8,
+        spatial_differencing: Option<SpatialDifferencing>,
+    ) -> ComplexPacking {
+        ComplexPacking {
+            simple_packing: SimplePacking {
+                reference_value: 0.0,
+                binary_scale_factor: 0,
+                decimal_scale_factor: 0,
+                bits_per_value: 4,
+                original_field_type: 0,
+            },
+            group_splitting_method: 1,
+            missing_value_management,
+            primary_missing_value_substitute: 0,
+            secondary_missing_value_substitute: 0,
+            number_of_groups: 3,
+            group_width_reference: 0,
+            group_width_bits: 2,
+            group_length_reference: 2,
+            group_length_increment: 1,
+            last_group_length: 3,
+            group_length_bits: 2,
+            spatial_differencing,
+        }
+    }
+
+    /// 複合圧縮された資料値をすべて展開する。
+    fn decode_complex_packing(
+        data: &[u8],
+        packing: &ComplexPacking,
+        number_of_values: u32,
+    ) -> anyhow::Result<Vec<Option<f64>>> {
+        let mut decoder = ComplexPackingDecoder::new(data, packing, number_of_values)?;
+        let mut bits = BitReader::new(data);
+        let mut values = Vec::new();
+        while let Some(value) = decoder.next(&mut bits)? {
+            values.push(value);
+        }
+
+        Ok(values)
+    }
+
+    fn decode(packing: &ComplexPacking, bytes: &[u8], number_of_values: u32) -> Vec<Option<f64>> {
+        let values = decode_complex_packing(bytes, packing, number_of_values).unwrap();
+        assert_eq!(values.len(), number_of_values as usize);
+
+        values
+    }
+
+    #[test]
+    fn decode_groups_without_missing_values() {
+        let packing = complex_packing(MISSING_VALUE_MANAGEMENT_NONE, None);
+        // グループの参照値: 5, 1, 9
+        // グループの幅: 2, 0, 3
+        // グループの長さ: 2 + 1 * 1 = 3, 2 + 0 * 1 = 2, 3（最後のグループは真の長さ）
+        let bytes = [
+            pack_bits(&[(5, 4), (1, 4), (9, 4)]),
+            pack_bits(&[(2, 2), (0, 2), (3, 2)]),
+            pack_bits(&[(1, 2), (0, 2), (0, 2)]),
+            pack_bits(&[(0, 2), (1, 2), (3, 2), (7, 3), (0, 3), (4, 3)]),
+        ]
+        .concat();
+        let expected = [5.0, 6.0, 8.0, 1.0, 1.0, 16.0, 9.0, 13.0];
+        let values = decode(&packing, &bytes, 8);
+        assert_eq!(values, expected.map(Some));
+    }
+
+    #[test]
+    fn decode_groups_with_missing_values() {
+        let packing = complex_packing(MISSING_VALUE_MANAGEMENT_PRIMARY_AND_SECONDARY, None);
+        // グループの参照値: 5, 15（第一欠測値）, 9
+        // グループの幅: 2, 0, 3
+        // グループの長さ: 3, 2, 3
+        let bytes = [
+            pack_bits(&[(5, 4), (15, 4), (9, 4)]),
+            pack_bits(&[(2, 2), (0, 2), (3, 2)]),
+            pack_bits(&[(1, 2), (0, 2), (0, 2)]),
+            pack_bits(&[(3, 2), (1, 2), (2, 2), (7, 3), (0, 3), (6, 3)]),
+        ]
+        .concat();
+        let expected = [None, Some(6.0), None, None, None, None, Some(9.0), None];
+        let values = decode(&packing, &bytes, 8);
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn decode_second_order_spatial_differencing() {
+        // 元の値: 10, 12, 15, 欠測, 19, 24, 30, 37
+        // 2次の空間差分: -, -, 1, -, 1, 1, 1, 1（最小値1を引くと0, 0, 0, 0, 0）
+        let differencing = SpatialDifferencing {
+            order: 2,
+            number_of_octets: 2,
+        };
+        let mut packing = complex_packing(MISSING_VALUE_MANAGEMENT_PRIMARY, Some(differencing));
+        packing.number_of_groups = 2;
+        packing.group_length_reference = 4;
+        packing.last_group_length = 4;
+        // 追加記述子: 10, 12, 最小値1
+        // グループの参照値: 0, 0
+        // グループの幅: 2, 0
+        // グループの長さ: 4, 4
+        let bytes = [
+            pack_bits(&[(10, 16), (12, 16), (1, 16)]),
+            pack_bits(&[(0, 4), (0, 4)]),
+            pack_bits(&[(2, 2), (0, 2)]),
+            pack_bits(&[(0, 2), (0, 2)]),
+            pack_bits(&[(0, 2), (0, 2), (0, 2), (3, 2)]),
+        ]
+        .concat();
+        let expected = [
+            Some(10.0),
+            Some(12.0),
+            Some(15.0),
+            None,
+            Some(19.0),
+            Some(24.0),
+            Some(30.0),
+            Some(37.0),
+        ];
+        let values = decode(&packing, &bytes, 8);
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn decode_first_order_spatial_differencing_with_negative_minimum() {
+        // 元の値: 100, 98, 97, 99
+        // 1次の空間差分: -, -2, -1, 2（最小値-2を引くと0, 1, 4）
+        let differencing = SpatialDifferencing {
+            order: 1,
+            number_of_octets: 1,
+        };
+        let mut packing = complex_packing(MISSING_VALUE_MANAGEMENT_NONE, Some(differencing));
+        packing.simple_packing.decimal_scale_factor = 1;
+        packing.number_of_groups = 1;
+        packing.last_group_length = 4;
+        // 追加記述子: 100, 最小値-2（符号ビット付き）
+        let bytes = [
+            pack_bits(&[(100, 8), (0x82, 8)]),
+            pack_bits(&[(0, 4)]),
+            pack_bits(&[(3, 2)]),
+            pack_bits(&[(0, 2)]),
+            pack_bits(&[(0, 3), (0, 3), (1, 3), (4, 3)]),
+        ]
+        .concat();
+        let expected = [10.0, 9.8, 9.7, 9.9];
+        let values = decode(&packing, &bytes, 4);
+        assert_eq!(values, expected.map(Some));
+    }
+
+    #[test]
+    fn reject_inconsistent_group_lengths() {
+        let packing = complex_packing(MISSING_VALUE_MANAGEMENT_NONE, None);
+        let bytes = [
+            pack_bits(&[(5, 4), (1, 4), (9, 4)]),
+            pack_bits(&[(0, 2), (0, 2), (0, 2)]),
+            pack_bits(&[(0, 2), (0, 2), (0, 2)]),
+        ]
+        .concat();
+        let result = decode_complex_packing(&bytes, &packing, 8);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use anyhow::anyhow;
+
+use crate::bit_reader::BitReader;
+use crate::complex_packing::ComplexPackingDecoder;
+use crate::{DataRepresentation, RunLength, RunLengthDecoder, Section5, SimplePacking};
+
+/// 同じ値が連続する資料値
+#[derive(Debug, Default)]
+pub(crate) struct ValueRun {
+    /// レベル値。ランレングス圧縮以外の場合は`None`
+    pub(crate) level: Option<u16>,
+    /// 物理値。欠測値の場合は`None`
+    pub(crate) value: Option<f64>,
+    /// 値が連続する資料値の数
+    pub(crate) count: u32,
+}
+
+/// 第5節の資料表現テンプレートに従って、資料値を順番に展開するデコーダー
+///
+/// CSVファイルへの変換、格子点の物理値の返却及び格子点のイテレーターは、このデコーダーで資料値を展開する。
+pub(crate) enum ValueDecoder {
+    /// 単純圧縮（資料表現テンプレート5.0）
+    SimplePacking {
+        /// 単純圧縮の資料表現
+        packing: SimplePacking,
+        /// 展開していない資料値の数
+        remaining: u32,
+    },
+    /// 複合圧縮（資料表現テンプレート5.2及び5.3）
+    ComplexPacking(ComplexPackingDecoder),
+    /// ランレングス圧縮（資料表現テンプレート5.200）
+    RunLength {
+        /// ランレングス圧縮の資料表現
+        run_length: RunLength,
+        /// ランレングス圧縮オクテット列のデコーダー
+        decoder: RunLengthDecoder,
+    },
+}
+
+impl ValueDecoder {
+    /// デコーダーを作成する。
+    ///
+    /// 単純圧縮の1データのビット数が0の場合、資料値は記録されておらず、すべての資料値の物理値は参照値から求める。
+    ///
+    /// # 引数
+    ///
+    /// * `section5` - 第5節の情報。
+    /// * `data` - 第7節の資料値。
+    pub(crate) fn new(section5: &Section5, data: &[u8]) -> anyhow::Result<Self> {
+        let length = data.len() as u64;
+        let number_of_values = section5.number_of_points;
+        match &section5.data_representation {
+            DataRepresentation::SimplePacking(packing) => {
+                let required_bits = number_of_values as u64 * packing.bits_per_value as u64;
+                if length * 8 < required_bits {
+                    return Err(anyhow!(
+                        "the packed values are shorter than expected (expected bits:{required_bits}, length:{length})"
+                    ));
+                }
+                Ok(Self::SimplePacking {
+                    packing: packing.clone(),
+                    remaining: number_of_values,
+                })
+            }
+            DataRepresentation::ComplexPacking(packing) => Ok(Self::ComplexPacking(
+                ComplexPackingDecoder::new(data, packing, number_of_values)?,
+            )),
+            DataRepresentation::RunLength(run_length) => Ok(Self::RunLength {
+                run_length: run_length.clone(),
+                decoder: RunLengthDecoder::new(
+                    length,
+                    run_length.bits_per_data,
+                    run_length.max_level_at_file,
+                    number_of_values,
+                )?,
+            }),
+        }
+    }
+
+    /// 次に同じ値が連続する資料値を展開して返却する。
+    ///
+    /// すべての資料値を展開した場合は`None`を返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `bits` - デコーダーを作成した第7節の資料値を読み込むリーダー。
+    pub(crate) fn next<B: AsRef<[u8]>>(
+        &mut self,
+        bits: &mut BitReader<B>,
+    ) -> anyhow::Result<Option<ValueRun>> {
+        match self {
+            Self::SimplePacking { packing, remaining } => {
+                if *remaining == 0 {
+                    return Ok(None);
+                }
+                *remaining -= 1;
+                let packed = bits.read(packing.bits_per_value as u32)?;
+                Ok(Some(ValueRun {
+                    level: None,
+                    value: Some(packing.value(packed as i64)),
+                    count: 1,
+                }))
+            }
+            Self::ComplexPacking(decoder) => Ok(decoder.next(bits)?.map(|value| ValueRun {
+                level: None,
+                value,
+                count: 1,
+            })),
+            Self::RunLength {
+                run_length,
+                decoder,
+            } => Ok(decoder.next(bits)?.map(|(level, count)| ValueRun {
+                level: Some(level),
+                value: run_length.representative_value(level),
+                count,
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_simple_packing_without_packed_values() {
+        // 1データのビット数が0の場合、すべての格子点の物理値は参照値
+        let packing = SimplePacking {
+            reference_value: 273.15,
+            binary_scale_factor: 0,
+            decimal_scale_factor: 0,
+            bits_per_value: 0,
+            original_field_type: 0,
+        };
+        let section5 = |packing: &SimplePacking| Section5 {
+            number_of_points: 4,
+            template: 0,
+            data_representation: DataRepresentation::SimplePacking(packing.clone()),
+        };
+        let mut decoder = ValueDecoder::new(&section5(&packing), &[]).unwrap();
+        let mut bits = BitReader::new(&[]);
+        let mut values = Vec::new();
+        while let Some(run) = decoder.next(&mut bits).unwrap() {
+            values.extend(std::iter::repeat_n(run.value.unwrap(), run.count as usize));
+        }
+        assert_eq!(values, vec![273.15f32 as f64; 4]);
+
+        // 資料値が不足している場合はエラー
+        let packing = SimplePacking {
+            bits_per_value: 12,
+            ..packing
+        };
+        assert!(ValueDecoder::new(&section5(&packing), &[0u8; 5]).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::anyhow;
+
+use crate::Section3;
+
+/// GRIB2報の第7節を展開した、格子点の物理値
+///
+/// 物理値は、資料の並び順（走査モード）にかかわらず、最初の格子点から数えたi方向（緯線またはx軸）と
+/// j方向（経線またはy軸）の位置で管理する。
+/// 格子点ごとに`Option`を保持しないように、欠測値またはビットマップで除外された格子点の物理値はNaNで表す。
+#[derive(Debug, Clone)]
+pub struct Grid {
+    /// 第3節情報
+    section3: Section3,
+    /// 物理値（j方向の行ごとに、i方向に並べた値）。欠測値はNaN
+    values: Vec<f64>,
+}
+
+impl Grid {
+    /// すべての格子点が欠測値の格子を作成する。
+    pub(crate) fn new(section3: Section3) -> anyhow::Result<Self> {
+        let number_of_points = section3.number_of_points_along_parallel as u64
+            * section3.number_of_points_along_meridian as u64;
+        if number_of_points != section3.number_of_points as u64 {
+            return Err(anyhow!(
+                "the number of points is different from Ni x Nj (number of points:{}, Ni:{}, Nj:{})",
+                section3.number_of_points,
+                section3.number_of_points_along_parallel,
+                section3.number_of_points_along_meridian
+            ));
+        }
+
+        Ok(Self {
+            values: vec![f64::NAN; section3.number_of_points as usize],
+            section3,
+        })
+    }
+
+    /// 資料の並び順で`index`番目の格子点から、`count`個の格子点に物理値を設定する。
+    pub(crate) fn set_values(
+        &mut self,
+        index: u32,
+        value: Option<f64>,
+        count: u32,
+    ) -> anyhow::Result<()> {
+        self.section3.check_point_range(index, count)?;
+        let Some(value) = value else {
+            return Ok(());
+        };
+        for index in index..index + count {
+            let (i, j) = self.section3.grid_index(index);
+            let position = self.position(i, j);
+            self.values[position] = value;
+        }
+
+        Ok(())
+    }
+
+    /// 第3節情報を返却する。
+    pub fn section3(&self) -> &Section3 {
+        &self.section3
+    }
+
+    /// i方向（緯線またはx軸）の格子点数（Ni）を返却する。
+    pub fn ni(&self) -> u32 {
+        self.section3.number_of_points_along_parallel
+    }
+
+    /// j方向（経線またはy軸）の格子点数（Nj）を返却する。
+    pub fn nj(&self) -> u32 {
+        self.section3.number_of_points_along_meridian
+    }
+
+    /// 最初の格子点からi方向に`i`番目、j方向に`j`番目の格子点の物理値を返却する。
+    ///
+    /// 格子の範囲外、欠測値またはビットマップで除外された格子点の場合は`None`を返却する。
+    pub fn get(&self, i: u32, j: u32) -> Option<f64> {
+        if self.ni() <= i || self.nj() <= j {
+            return None;
+        }
+
+        let value = self.values[self.position(i, j)];
+
+        (!value.is_nan()).then_some(value)
+    }
+
+    /// 緯度と経度に最も近い格子点の物理値を返却する。
+    ///
+    /// 格子の範囲外、欠測値またはビットマップで除外された格子点の場合は`None`を返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `latitude` - 緯度（10^6度単位、南緯は負）。
+    /// * `longitude` - 経度（10^6度単位、西経は負）。
+    pub fn at(&self, latitude: i32, longitude: i32) -> Option<f64> {
+        let (i, j) = self.section3.nearest_grid_index(longitude, latitude)?;
+
+        self.get(i, j)
+    }
+
+    /// 最初の格子点からi方向に`i`番目、j方向に`j`番目の格子点の経度と緯度（10^6度単位）を返却する。
+    pub fn coordinate(&self, i: u32, j: u32) -> (i32, i32) {
+        self.section3.point_coordinate(i, j)
+    }
+
+    /// 最初の格子点から、j方向の行ごとに、i方向に並べた物理値を返却する。
+    ///
+    /// 欠測値またはビットマップで除外された格子点の物理値はNaN。
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// j方向の行ごとに、i方向に並べた物理値を返却するイテレーターを返却する。
+    ///
+    /// 最初の行は、最初の格子点を含む行。欠測値またはビットマップで除外された格子点の物理値はNaN。
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, f64> {
+        self.values.chunks_exact(self.ni().max(1) as usize)
+    }
+
+    /// 最初の格子点からi方向に`i`番目、j方向に`j`番目の格子点の、物理値のインデックスを返却する。
+    fn position(&self, i: u32, j: u32) -> usize {
+        j as usize * self.ni() as usize + i as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::Ellipsoid;
+    use crate::{EarthShape, GridDefinition, LatLonGrid, EARTH_SHAPE_GRS80};
+
+    /// NaNの物理値を`None`に置き換える。
+    fn optional(values: &[f64]) -> Vec<Option<f64>> {
+        values
+            .iter()
+            .map(|&value| (!value.is_nan()).then_some(value))
+            .collect()
+    }
+
+    /// 格子間隔が1度の緯度・経度格子の第3節情報を返却する。
+    fn lat_lon_section3(
+        scanning_mode: u8,
+        first_longitude: i32,
+        first_latitude: i32,
+        ni: u32,
+        nj: u32,
+    ) -> Section3 {
+        Section3 {
+            number_of_points: ni * nj,
+            number_of_points_along_parallel: ni,
+            number_of_points_along_meridian: nj,
+            template: 0,
+            earth_shape: EarthShape::new(EARTH_SHAPE_GRS80, Ellipsoid::grs80()),
+            first_latitude: first_latitude * 1000000,
+            first_longitude: first_longitude * 1000000,
+            scanning_mode,
+            grid_definition: GridDefinition::LatLon(LatLonGrid::new(
+                (first_longitude * 1000000, first_latitude * 1000000),
+                (0, 0),
+                (1000000, 1000000),
+                (ni, nj),
+                scanning_mode,
+            )),
+        }
+    }
+
+    #[test]
+    fn values_are_arranged_by_grid_index() {
+        // 経度130度から132度、緯度38度から40度の3x3の格子で、隣接する行を逆方向に走査する
+        let mut grid = Grid::new(lat_lon_section3(0x10, 130, 40, 3, 3)).unwrap();
+        grid.set_values(0, Some(1.0), 4).unwrap();
+        grid.set_values(4, None, 2).unwrap();
+        grid.set_values(6, Some(2.0), 3).unwrap();
+        assert!(grid.set_values(8, Some(3.0), 2).is_err());
+
+        assert_eq!(grid.ni(), 3);
+        assert_eq!(grid.nj(), 3);
+        let rows: Vec<Vec<Option<f64>>> = grid.rows().map(optional).collect();
+        assert_eq!(
+            rows,
+            [
+                [Some(1.0), Some(1.0), Some(1.0)],
+                [None, None, Some(1.0)],
+                [Some(2.0), Some(2.0), Some(2.0)],
+            ]
+        );
+        assert!(grid.values()[3].is_nan());
+        assert_eq!(grid.get(2, 1), Some(1.0));
+        assert_eq!(grid.get(0, 1), None);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.coordinate(2, 1), (132000000, 39000000));
+        // 最も近い格子点の物理値
+        assert_eq!(grid.at(39400000, 132300000), Some(1.0));
+        assert_eq!(grid.at(37600000, 129800000), Some(2.0));
+        assert_eq!(grid.at(40600000, 131000000), None);
+        assert_eq!(grid.at(39000000, 132600000), None);
+    }
+
+    #[test]
+    fn nearest_point_wraps_around_antimeridian() {
+        // 経度0度から359度、緯度が南から北に並ぶ全球の格子
+        let mut grid = Grid::new(lat_lon_section3(0x40, 0, -1, 360, 3)).unwrap();
+        for index in 0..grid.section3().number_of_points {
+            grid.set_values(index, Some(index as f64), 1).unwrap();
+        }
+        assert_eq!(grid.at(-1000000, 0), Some(0.0));
+        assert_eq!(grid.at(1000000, -1000000), Some(720.0 + 359.0));
+        // 最後の格子点より東の地点は、最初の格子点に近い
+        assert_eq!(grid.at(0, -300000), Some(360.0));
+        assert_eq!(grid.at(0, 180000000), Some(360.0 + 180.0));
+        // 格子点数と資料点数が一致しない場合はエラー
+        let mut section3 = lat_lon_section3(0x40, 0, -1, 360, 3);
+        section3.number_of_points += 1;
+        assert!(Grid::new(section3).is_err());
+    }
+}
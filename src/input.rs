@@ -0,0 +1,223 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::anyhow;
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use xz2::read::XzDecoder;
+
+/// gzip形式のマジックナンバー
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// bzip2形式のマジックナンバー
+const BZIP2_MAGIC: &[u8] = b"BZh";
+/// xz形式のマジックナンバー
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+
+/// 展開したファイル及びtarアーカイブに格納されたファイルをメモリに読み込む、最大のバイト数（1GiB）
+///
+/// 小さな圧縮ファイルが膨大なバイト数に展開される場合に、メモリを使い果たさないように制限する。
+pub(crate) const MAX_IN_MEMORY_SIZE: u64 = 1 << 30;
+
+/// 入力ファイルの圧縮形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    /// 圧縮されていない
+    None,
+    /// gzip形式
+    Gzip,
+    /// bzip2形式
+    Bzip2,
+    /// xz形式
+    Xz,
+}
+
+impl Compression {
+    /// ファイルの先頭のバイト列（マジックナンバー）から、圧縮形式を判定する。
+    fn detect(head: &[u8]) -> Self {
+        if head.starts_with(GZIP_MAGIC) {
+            Self::Gzip
+        } else if head.starts_with(BZIP2_MAGIC) {
+            Self::Bzip2
+        } else if head.starts_with(XZ_MAGIC) {
+            Self::Xz
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// GRIB2ファイルのリーダー
+///
+/// 圧縮されていないファイルはそのまま読み込み、圧縮されたファイルは展開した内容をメモリ上で読み込む。
+/// GRIB2報の読み込みでは任意の位置にシークするため、圧縮されたファイルは展開しながら全体をメモリに読み込む。
+/// 展開したバイト数が[`MAX_IN_MEMORY_SIZE`]（1GiB）を超える場合はエラーになる。
+pub enum InputReader {
+    /// 圧縮されていないファイル
+    File(BufReader<File>),
+    /// メモリ上のGRIB2報（展開したファイルの内容を含む）
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl InputReader {
+    /// ファイルを開いて、マジックナンバーから判定した圧縮形式に従って展開するリーダーを返却する。
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let mut reader = BufReader::new(File::open(path.as_ref())?);
+        match Compression::detect(reader.fill_buf()?) {
+            Compression::None => Ok(Self::File(reader)),
+            compression => Ok(Self::Memory(Cursor::new(decompress(reader, compression)?))),
+        }
+    }
+
+    /// メモリ上のバイト列を、マジックナンバーから判定した圧縮形式に従って展開するリーダーを返却する。
+    ///
+    /// 標準入力のようにシークできない入力を、すべて読み込んでから変換する場合に使用する。
+    pub fn from_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let bytes = match decompress_bytes(&bytes)? {
+            Cow::Borrowed(_) => bytes,
+            Cow::Owned(decompressed) => decompressed,
+        };
+
+        Ok(Self::Memory(Cursor::new(bytes)))
+    }
+}
+
+/// メモリ上のバイト列を、マジックナンバーから判定した圧縮形式に従って展開する。
+///
+/// 圧縮されていない場合は、バイト列を複製せずにそのまま返却する。
+pub(crate) fn decompress_bytes(bytes: &[u8]) -> anyhow::Result<Cow<'_, [u8]>> {
+    match Compression::detect(bytes) {
+        Compression::None => Ok(Cow::Borrowed(bytes)),
+        compression => Ok(Cow::Owned(decompress(bytes, compression)?)),
+    }
+}
+
+/// ファイルを開いて、マジックナンバーから判定した圧縮形式に従って、先頭から順番に展開するストリームを返却する。
+///
+/// 展開した内容をメモリに保持しないため、シークできない。
+pub(crate) fn open_stream<P: AsRef<Path>>(path: P) -> anyhow::Result<Box<dyn Read>> {
+    let mut reader = BufReader::new(File::open(path.as_ref())?);
+    let compression = Compression::detect(reader.fill_buf()?);
+
+    Ok(decoder(reader, compression))
+}
+
+/// 圧縮形式に従って、読み込みながら展開するストリームを返却する。
+fn decoder<'a, R: BufRead + 'a>(reader: R, compression: Compression) -> Box<dyn Read + 'a> {
+    match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(MultiGzDecoder::new(reader)),
+        Compression::Bzip2 => Box::new(MultiBzDecoder::new(reader)),
+        Compression::Xz => Box::new(XzDecoder::new_multi_decoder(reader)),
+    }
+}
+
+/// 圧縮されたバイト列を、ストリームとして読み込みながら展開する。
+///
+/// 展開したバイト数が[`MAX_IN_MEMORY_SIZE`]を超える場合はエラーを返却する。
+fn decompress<R: BufRead>(reader: R, compression: Compression) -> anyhow::Result<Vec<u8>> {
+    read_to_limit(decoder(reader, compression), MAX_IN_MEMORY_SIZE)
+        .map_err(|e| anyhow!("failed to decompress a {compression:?} input: {e}"))
+}
+
+/// ストリームを最後まで読み込んで、読み込んだバイト列を返却する。
+///
+/// 読み込んだバイト数が`limit`を超える場合は、それ以上読み込まずにエラーを返却する。
+pub(crate) fn read_to_limit<R: Read>(reader: R, limit: u64) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut bytes)?;
+    if limit < bytes.len() as u64 {
+        return Err(anyhow!("the input exceeds {limit} bytes"));
+    }
+
+    Ok(bytes)
+}
+
+impl Read for InputReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::File(reader) => reader.read(buf),
+            Self::Memory(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for InputReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        match self {
+            Self::File(reader) => reader.fill_buf(),
+            Self::Memory(reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match self {
+            Self::File(reader) => reader.consume(amt),
+            Self::Memory(reader) => reader.consume(amt),
+        }
+    }
+}
+
+impl Seek for InputReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Self::File(reader) => reader.seek(pos),
+            Self::Memory(reader) => reader.seek(pos),
+        }
+    }
+
+    fn seek_relative(&mut self, offset: i64) -> std::io::Result<()> {
+        match self {
+            Self::File(reader) => reader.seek_relative(offset),
+            Self::Memory(reader) => reader.seek_relative(offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_compression_from_magic_number() {
+        assert_eq!(Compression::detect(b"GRIB\0\0\0\x02"), Compression::None);
+        assert_eq!(Compression::detect(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+        assert_eq!(Compression::detect(b"BZh91AY&SY"), Compression::Bzip2);
+        assert_eq!(
+            Compression::detect(&[0xfd, b'7', b'z', b'X', b'Z', 0x00, 0x00]),
+            Compression::Xz
+        );
+        assert_eq!(Compression::detect(&[]), Compression::None);
+    }
+
+    #[test]
+    fn decompress_bytes_in_memory() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(b"GRIB").unwrap();
+        let mut reader = InputReader::from_bytes(encoder.finish().unwrap()).unwrap();
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, b"GRIB");
+        // 圧縮されていないバイト列はそのまま読み込む
+        let mut reader = InputReader::from_bytes(b"GRIB".to_vec()).unwrap();
+        bytes.clear();
+        reader.read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, b"GRIB");
+    }
+
+    #[test]
+    fn read_to_limit_rejects_too_large_inputs() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&[0u8; 4096]).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let bytes = read_to_limit(MultiGzDecoder::new(&compressed[..]), 4096).unwrap();
+        assert_eq!(bytes.len(), 4096);
+        // 展開したバイト数が上限を超える
+        assert!(read_to_limit(MultiGzDecoder::new(&compressed[..]), 4095).is_err());
+    }
+}
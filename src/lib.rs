@@ -1,13 +1,43 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
+use std::cell::{RefCell, RefMut};
+use std::collections::VecDeque;
 use std::fs::{File, OpenOptions};
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufRead, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 use std::str;
 
 use anyhow::anyhow;
-use time::{Date, Month, PrimitiveDateTime, Time};
+use time::{Date, Duration, Month, PrimitiveDateTime, Time};
+
+mod archive;
+mod area;
+mod bit_reader;
+mod bitmap;
+mod complex_packing;
+mod decoder;
+mod grid;
+mod input;
+mod mesh;
+mod points;
+mod projection;
+mod zonal;
+
+use bit_reader::BitReader;
+use bitmap::{count_defined_points, BitmapExpander};
+use complex_packing::validate_missing_value_management;
+use decoder::ValueDecoder;
+use input::decompress_bytes;
+use projection::{ConicProjection, Ellipsoid, ProjectedPlane};
+use zonal::{csv_field, ZonalAccumulator};
+
+pub use archive::{ArchiveMember, Grib2Archive};
+pub use area::{Area, AreaMode};
+pub use grid::Grid;
+pub use input::InputReader;
+pub use mesh::{mesh_center, mesh_code, MeshCodes, MeshLevel};
+pub use points::{GridPoint, GridPoints};
+pub use zonal::{ZonalStatistics, Zone};
 
-type FileReader = BufReader<File>;
 type FileWriter = BufWriter<File>;
 
 /// 第0節 資料分野: 気象分野
@@ -25,42 +55,149 @@ const DOCUMENT_KIND: u8 = 0;
 /// 第3節 格子系定義の出典: 緯度／経度格子（正距円筒図法又はプレートカリー図法）
 const GRID_SYSTEM_DEFINITION: u8 = 0;
 /// 第3節 格子系定義のテンプレート番号: 緯度・経度格子
-const GRID_SYSTEM_DEFINITION_TEMPLATE: u16 = 0;
-/// 第3節 地球の形状: GRS80回転楕円体
-const EARTH_FIGURE: u8 = 4;
-/// 第3節 緯線に沿った格子点数: 2560
-const NUMBER_OF_POINT_AT_VERTICAL: u32 = 2_560;
-/// 第3節 経線に沿った格子点数: 2560
-const NUMBER_OF_POINT_AT_HORIZONTAL: u32 = 3_360;
+const GRID_SYSTEM_DEFINITION_TEMPLATE_LAT_LON: u16 = 0;
+/// 第3節 格子系定義のテンプレート番号: ポーラーステレオ図法
+const GRID_SYSTEM_DEFINITION_TEMPLATE_POLAR_STEREOGRAPHIC: u16 = 20;
+/// 第3節 格子系定義のテンプレート番号: ランベルト正角円錐図法
+const GRID_SYSTEM_DEFINITION_TEMPLATE_LAMBERT_CONFORMAL: u16 = 30;
+/// 第3節 投影中心フラグ: 南極が投影面上にある（フラグ表3.5 ビット1）
+const PROJECTION_CENTRE_SOUTH_POLE: u8 = 0x80;
+/// 第3節 投影中心フラグ: 双極かつ対称（フラグ表3.5 ビット2）
+const PROJECTION_CENTRE_BIPOLAR: u8 = 0x40;
+/// 地球の形状（符号表3.2）: 半径6,367,470.0mの球体
+const EARTH_SHAPE_SPHERE_6367470: u8 = 0;
+/// 地球の形状（符号表3.2）: 作成中枢が半径を指定した球体
+const EARTH_SHAPE_SPHERE_SPECIFIED: u8 = 1;
+/// 地球の形状（符号表3.2）: IAU 1965の回転楕円体
+const EARTH_SHAPE_IAU1965: u8 = 2;
+/// 地球の形状（符号表3.2）: 作成中枢が長軸と短軸をキロメートル単位で指定した回転楕円体
+const EARTH_SHAPE_SPHEROID_SPECIFIED_KM: u8 = 3;
+/// 地球の形状（符号表3.2）: IAG-GRS80回転楕円体
+const EARTH_SHAPE_GRS80: u8 = 4;
+/// 地球の形状（符号表3.2）: WGS84回転楕円体
+const EARTH_SHAPE_WGS84: u8 = 5;
+/// 地球の形状（符号表3.2）: 半径6,371,229.0mの球体
+const EARTH_SHAPE_SPHERE_6371229: u8 = 6;
+/// 地球の形状（符号表3.2）: 作成中枢が長軸と短軸をメートル単位で指定した回転楕円体
+const EARTH_SHAPE_SPHEROID_SPECIFIED_M: u8 = 7;
+/// 地球の形状（符号表3.2）: 半径6,371,200mの球体（WGS84の測地系）
+const EARTH_SHAPE_SPHERE_6371200: u8 = 8;
+/// 地球の形状（符号表3.2）: OSGB 1936（Airy 1830）の回転楕円体
+const EARTH_SHAPE_OSGB1936: u8 = 9;
 /// 第3節 原作成領域の基本角
 const CREATION_RANGE_ANGLE: u32 = 0;
-/// 第3節 走査モード
-const SCANNING_MODE: u8 = 0x00;
+/// 第3節 走査モード: i方向負（東から西）に走査（フラグ表3.4 ビット1）
+const SCANNING_MODE_I_NEGATIVE: u8 = 0x80;
+/// 第3節 走査モード: j方向正（南から北）に走査（フラグ表3.4 ビット2）
+const SCANNING_MODE_J_POSITIVE: u8 = 0x40;
+/// 第3節 走査モード: j方向の格子点が連続（フラグ表3.4 ビット3）
+const SCANNING_MODE_J_CONSECUTIVE: u8 = 0x20;
+/// 第3節 走査モード: 隣接する行を逆方向に走査（フラグ表3.4 ビット4）
+const SCANNING_MODE_BOUSTROPHEDON: u8 = 0x10;
+/// 第3節 走査モード: 対応しているフラグ
+const SUPPORTED_SCANNING_MODE: u8 = SCANNING_MODE_I_NEGATIVE
+    | SCANNING_MODE_J_POSITIVE
+    | SCANNING_MODE_J_CONSECUTIVE
+    | SCANNING_MODE_BOUSTROPHEDON;
+/// 第5節 資料表現テンプレート番号: 格子点資料－単純圧縮
+const DOCUMENT_EXPRESSION_TEMPLATE_SIMPLE_PACKING: u16 = 0;
+/// 第5節 資料表現テンプレート番号: 格子点資料－複合圧縮
+const DOCUMENT_EXPRESSION_TEMPLATE_COMPLEX_PACKING: u16 = 2;
+/// 第5節 資料表現テンプレート番号: 格子点資料－複合圧縮と空間差分
+const DOCUMENT_EXPRESSION_TEMPLATE_SPATIAL_DIFFERENCING: u16 = 3;
 /// 第5節 資料表現テンプレート番号: ランレングス圧縮
-const DOCUMENT_EXPRESSION_TEMPLATE: u16 = 200;
-/// 第5節 1データのビット数
-const BITS_PER_DATA: u8 = 8;
-/// 第5節 データ代表値の尺度因子
-const DATA_VALUE_FACTOR: u8 = 1;
+const DOCUMENT_EXPRESSION_TEMPLATE_RUN_LENGTH: u16 = 200;
+/// 第5節 ランレングス圧縮の1データのビット数の最大値
+const MAX_BITS_PER_DATA: u8 = 16;
+/// 第5節 単純圧縮の1データのビット数の最大値
+const MAX_BITS_PER_PACKED_VALUE: u8 = 32;
 
 /// GRIB2ファイル・コンバーター
-pub struct Grib2Csv {
-    reader: RefCell<FileReader>,
-    section3: Section3,
-    section5: Section5,
+///
+/// GRIB2報は、`Read`、`BufRead`及び`Seek`を実装した任意のリーダーから読み込む。
+/// 既定では、ファイルを読み込み、gzip、bzip2またはxz形式で圧縮されている場合は展開する。
+pub struct Grib2Csv<R = InputReader> {
+    reader: RefCell<R>,
+    messages: Vec<Grib2Message>,
     with_header: bool,
+    /// CSVファイルに出力する地域メッシュ・コードの区画の種類
+    mesh_level: Option<MeshLevel>,
 }
 
-#[derive(Default)]
+/// GRIB2報
+///
+/// 降水短時間予報のように、1つのファイルに複数のGRIB2報が記録されている場合、GRIB2報ごとに
+/// インスタンスを構築する。
+/// MSMやGSMのように、1つのGRIB2報に第4節から第7節（第3節を含む場合がある）が繰り返し記録されている場合は、
+/// 繰り返しごとの資料を、それぞれ1つのGRIB2報として扱う。
+pub struct Grib2Message {
+    /// ファイルの先頭からGRIB2報の先頭までのバイト数
+    offset: u64,
+    /// GRIB2報のメタデータ
+    metadata: Grib2Metadata,
+    /// 第6節のビットマップ
+    bitmap: Option<Bitmap>,
+    /// ファイルの先頭から第7節の先頭までのバイト数
+    section7_offset: u64,
+}
+
+impl Grib2Message {
+    /// ファイルの先頭からGRIB2報の先頭までのバイト数を返却する。
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// GRIB2報のメタデータを返却する。
+    pub fn metadata(&self) -> &Grib2Metadata {
+        &self.metadata
+    }
+}
+
+/// GRIB2報のメタデータ
+///
+/// 第0節、第1節、第3節、第4節及び第5節に記録されている情報を格納する。
+#[derive(Debug, Clone)]
+pub struct Grib2Metadata {
+    /// 第0節情報
+    pub section0: Section0,
+    /// 第1節情報
+    pub section1: Section1,
+    /// 第3節情報
+    pub section3: Section3,
+    /// 第4節情報
+    pub section4: Section4,
+    /// 第5節情報
+    pub section5: Section5,
+    /// 第6節情報
+    pub section6: Section6,
+}
+
+#[derive(Clone, Default)]
 pub struct Boundary {
-    northernmost: Option<u32>,
-    southernmost: Option<u32>,
-    westernmost: Option<u32>,
-    easternmost: Option<u32>,
+    northernmost: Option<i32>,
+    southernmost: Option<i32>,
+    westernmost: Option<i32>,
+    easternmost: Option<i32>,
+    /// 格子点を出力する地域メッシュ
+    mesh_codes: Option<MeshCodes>,
+    /// 格子点を出力する領域
+    area: Option<Area>,
+    /// 格子点を領域に含める条件
+    area_mode: AreaMode,
 }
 
 impl Boundary {
-    fn contains(&self, longitude: u32, latitude: u32) -> bool {
+    /// 格子点が境界に含まれるか確認する。
+    ///
+    /// 経度は-180度より大きく180度以下に正規化して比較する。
+    /// 正規化した最西端の経度が最東端の経度より大きい場合は、日付変更線（経度180度）をまたぐ範囲として扱う。
+    /// 地域メッシュ・コードが指定されている場合は、いずれかの地域メッシュに含まれる格子点のみを境界に含める。
+    fn contains(&self, longitude: i32, latitude: i32) -> bool {
+        if let Some(mesh_codes) = &self.mesh_codes {
+            if !mesh_codes.contains(longitude, latitude) {
+                return false;
+            }
+        }
         if let Some(northernmost) = self.northernmost {
             if northernmost < latitude {
                 return false;
@@ -71,60 +208,166 @@ impl Boundary {
                 return false;
             }
         }
-        if let Some(westernmost) = self.westernmost {
-            if longitude < westernmost {
-                return false;
-            }
-        }
-        if let Some(easternmost) = self.easternmost {
-            if easternmost < longitude {
-                return false;
+        let longitude = normalize_longitude(longitude as i64);
+        match (self.westernmost, self.easternmost) {
+            (Some(westernmost), Some(easternmost)) => {
+                if FULL_CIRCLE <= easternmost as i64 - westernmost as i64 {
+                    return true;
+                }
+                let westernmost = normalize_longitude(westernmost as i64);
+                let easternmost = normalize_longitude(easternmost as i64);
+                if westernmost <= easternmost {
+                    westernmost <= longitude && longitude <= easternmost
+                } else {
+                    westernmost <= longitude || longitude <= easternmost
+                }
             }
+            (Some(westernmost), None) => normalize_longitude(westernmost as i64) <= longitude,
+            (None, Some(easternmost)) => longitude <= normalize_longitude(easternmost as i64),
+            (None, None) => true,
         }
+    }
+
+    /// 格子点が境界に含まれ、領域が指定されている場合は、格子点または格子点を中心とする格子が領域に含まれるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `section3` - 第3節情報。
+    /// * `index` - 格子点の、資料の並び順でのインデックス。
+    /// * `longitude` - 格子点の経度（10^6度単位）。
+    /// * `latitude` - 格子点の緯度（10^6度単位）。
+    fn contains_point(
+        &self,
+        section3: &Section3,
+        index: u32,
+        longitude: i32,
+        latitude: i32,
+    ) -> bool {
+        self.contains(longitude, latitude)
+            && self.area.as_ref().is_none_or(|area| {
+                area.includes(self.area_mode, longitude, latitude, || {
+                    section3.cell_corners(index)
+                })
+            })
+    }
+}
+
+/// 1周の経度（10^6度単位）
+const FULL_CIRCLE: i64 = 360_000_000;
 
-        true
+/// 経度（10^6度単位）を、-180度より大きく180度以下に正規化する。
+fn normalize_longitude(longitude: i64) -> i32 {
+    let longitude = longitude.rem_euclid(FULL_CIRCLE);
+    if FULL_CIRCLE / 2 < longitude {
+        (longitude - FULL_CIRCLE) as i32
+    } else {
+        longitude as i32
     }
 }
 
 #[derive(Default)]
 pub struct BoundaryBuilder {
-    northernmost: Option<u32>,
-    southernmost: Option<u32>,
-    westernmost: Option<u32>,
-    easternmost: Option<u32>,
+    northernmost: Option<i32>,
+    southernmost: Option<i32>,
+    westernmost: Option<i32>,
+    easternmost: Option<i32>,
+    mesh_codes: Option<MeshCodes>,
+    area: Option<Area>,
+    area_mode: AreaMode,
 }
 
 impl BoundaryBuilder {
-    pub fn northernmost(mut self, degree: Option<u32>) -> Self {
+    pub fn northernmost(mut self, degree: Option<i32>) -> Self {
         self.northernmost = degree;
 
         self
     }
 
-    pub fn southernmost(mut self, degree: Option<u32>) -> Self {
+    pub fn southernmost(mut self, degree: Option<i32>) -> Self {
         self.southernmost = degree;
 
         self
     }
 
-    pub fn westernmost(mut self, degree: Option<u32>) -> Self {
+    pub fn westernmost(mut self, degree: Option<i32>) -> Self {
         self.westernmost = degree;
 
         self
     }
 
-    pub fn easternmost(mut self, degree: Option<u32>) -> Self {
+    pub fn easternmost(mut self, degree: Option<i32>) -> Self {
         self.easternmost = degree;
 
         self
     }
 
+    pub fn mesh_codes(mut self, codes: Option<MeshCodes>) -> Self {
+        self.mesh_codes = codes;
+
+        self
+    }
+
+    pub fn area(mut self, area: Option<Area>) -> Self {
+        self.area = area;
+
+        self
+    }
+
+    pub fn area_mode(mut self, mode: AreaMode) -> Self {
+        self.area_mode = mode;
+
+        self
+    }
+
     pub fn build(self) -> Boundary {
         Boundary {
             northernmost: self.northernmost,
             southernmost: self.southernmost,
             westernmost: self.westernmost,
             easternmost: self.easternmost,
+            mesh_codes: self.mesh_codes,
+            area: self.area,
+            area_mode: self.area_mode,
+        }
+    }
+}
+
+/// GRIB2報の検証方針
+///
+/// 既定では、作成ステータスが現業プロダクト、かつ資料の種類が解析プロダクトのGRIB2報のみを受け入れる。
+#[derive(Clone, Copy, Default)]
+pub struct ValidationPolicy {
+    /// 現業プロダクト以外の作成ステータスを受け入れるかを示すフラグ
+    accept_test_products: bool,
+    /// 解析プロダクト以外の資料の種類を受け入れるかを示すフラグ
+    accept_any_document_kinds: bool,
+}
+
+#[derive(Default)]
+pub struct ValidationPolicyBuilder {
+    accept_test_products: bool,
+    accept_any_document_kinds: bool,
+}
+
+impl ValidationPolicyBuilder {
+    /// 試験データなど、現業プロダクト以外の作成ステータスを受け入れるかを設定する。
+    pub fn accept_test_products(mut self, accept: bool) -> Self {
+        self.accept_test_products = accept;
+
+        self
+    }
+
+    /// 予報プロダクトなど、解析プロダクト以外の資料の種類を受け入れるかを設定する。
+    pub fn accept_any_document_kinds(mut self, accept: bool) -> Self {
+        self.accept_any_document_kinds = accept;
+
+        self
+    }
+
+    pub fn build(self) -> ValidationPolicy {
+        ValidationPolicy {
+            accept_test_products: self.accept_test_products,
+            accept_any_document_kinds: self.accept_any_document_kinds,
         }
     }
 }
@@ -132,6 +375,8 @@ impl BoundaryBuilder {
 impl Grib2Csv {
     /// コンストラクタ
     ///
+    /// ファイルがgzip、bzip2またはxz形式で圧縮されている場合は、マジックナンバーから圧縮形式を判定して展開する。
+    ///
     /// # 引数
     ///
     /// * `path` - grib2ファイルのパス。
@@ -141,36 +386,117 @@ impl Grib2Csv {
     ///
     /// GRIB2Infoインスタンス。
     pub fn new<P: AsRef<Path>>(path: P, with_header: bool) -> anyhow::Result<Self> {
-        let mut reader = BufReader::new(File::open(path.as_ref())?);
-        // 第0節を読み込み
-        read_section0(&mut reader)?;
-        // 第1節を読み込み
-        read_section1(&mut reader)?;
-        // 第3節を読み込み
-        let section3 = read_section3(&mut reader)?;
-        // 第4節を読み込み
-        read_section4(&mut reader)?;
-        // 第5節を読み込み
-        let section5 = read_section5(&mut reader)?;
-        if section3.number_of_points != section5.number_of_points {
-            return Err(anyhow!(
-                "the number of points is different (section3:{}, section5:{})",
-                section3.number_of_points,
-                section5.number_of_points
-            ));
+        Self::with_policy(path, with_header, ValidationPolicy::default())
+    }
+
+    /// GRIB2報の検証方針を指定するコンストラクタ
+    ///
+    /// # 引数
+    ///
+    /// * `path` - grib2ファイルのパス。
+    /// * `with_header` - ヘッダ出力フラグ。
+    /// * `policy` - GRIB2報の検証方針。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB2Infoインスタンス。
+    pub fn with_policy<P: AsRef<Path>>(
+        path: P,
+        with_header: bool,
+        policy: ValidationPolicy,
+    ) -> anyhow::Result<Self> {
+        Self::from_reader(InputReader::open(path)?, with_header, policy)
+    }
+}
+
+impl<'a> Grib2Csv<Cursor<Cow<'a, [u8]>>> {
+    /// メモリ上のGRIB2報を読み込むコンストラクタ
+    ///
+    /// ファイルから読み込む場合と同様に、バイト列がgzip、bzip2またはxz形式で圧縮されている場合は、
+    /// マジックナンバーから圧縮形式を判定して展開する。
+    /// 展開した内容はメモリに保持するため、展開したバイト数が1GiBを超える場合はエラーになる。
+    /// 圧縮されていない場合は、バイト列を複製せずに読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `bytes` - GRIB2報を記録したバイト列。
+    /// * `with_header` - ヘッダ出力フラグ。
+    /// * `policy` - GRIB2報の検証方針。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB2Infoインスタンス。
+    pub fn from_bytes(
+        bytes: &'a [u8],
+        with_header: bool,
+        policy: ValidationPolicy,
+    ) -> anyhow::Result<Self> {
+        Self::from_reader(Cursor::new(decompress_bytes(bytes)?), with_header, policy)
+    }
+}
+
+impl<R: BufRead + Seek> Grib2Csv<R> {
+    /// 任意のリーダーからGRIB2報を読み込むコンストラクタ
+    ///
+    /// 標準入力のようにシークできない入力は、`Vec<u8>`などに読み込んでから`Cursor`で包んで渡すこと。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2報の先頭に位置するリーダー。
+    /// * `with_header` - ヘッダ出力フラグ。
+    /// * `policy` - GRIB2報の検証方針。
+    ///
+    /// # 戻り値
+    ///
+    /// GRIB2Infoインスタンス。
+    pub fn from_reader(
+        mut reader: R,
+        with_header: bool,
+        policy: ValidationPolicy,
+    ) -> anyhow::Result<Self> {
+        // ファイルに記録されているすべてのGRIB2報を読み込み
+        let messages =
+            MessageReader::new(&mut reader, policy).collect::<anyhow::Result<Vec<_>>>()?;
+        if messages.is_empty() {
+            return Err(anyhow!("the file does not contain any grib2 message"));
         }
-        // 第6節を読み込み
-        read_section6(&mut reader)?;
 
         Ok(Self {
             reader: RefCell::new(reader),
-            section3,
-            section5,
+            messages,
             with_header,
+            mesh_level: None,
         })
     }
 
-    /// GRIB2ファイルの第7節を読み込んで、データをCSV形式のファイルに出力する。
+    /// CSVファイルに、格子点を含む地域メッシュ（JIS X 0410）のコードを出力するように設定する。
+    ///
+    /// 地域メッシュ・コードは、物理値の後の列に出力する。地域メッシュ・コードを定義できない格子点の列は空欄。
+    ///
+    /// # 引数
+    ///
+    /// * `level` - 地域メッシュの区画の種類。`None`の場合は地域メッシュ・コードを出力しない。
+    pub fn with_mesh_level(mut self, level: Option<MeshLevel>) -> Self {
+        self.mesh_level = level;
+        self
+    }
+
+    /// ファイルに記録されているGRIB2報の数を返却する。
+    pub fn number_of_messages(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// ファイルの最初のGRIB2報のメタデータを返却する。
+    pub fn metadata(&self) -> &Grib2Metadata {
+        self.messages[0].metadata()
+    }
+
+    /// ファイルに記録されているGRIB2報を、記録されている順番で走査するイテレーターを返却する。
+    pub fn messages(&self) -> std::slice::Iter<'_, Grib2Message> {
+        self.messages.iter()
+    }
+
+    /// ファイルの最初のGRIB2報の第7節を読み込んで、データをCSV形式のファイルに出力する。
     ///
     /// GRIB2ファイルを正確に読み込みできたか確認するために、処理の最後で第8節を読み込み、
     /// "7777"を読み込めるか確認する。
@@ -180,177 +506,657 @@ impl Grib2Csv {
     /// * `path` - 変換後のデータを記録するCSV形式のファイルのパス。
     /// * `boundary` - CSVファイルに出力する格子点の境界。
     pub fn convert<P: AsRef<Path>>(&self, path: P, boundary: Boundary) -> anyhow::Result<()> {
+        self.convert_message(0, path, boundary)
+    }
+
+    /// 指定されたGRIB2報の第7節を読み込んで、データをCSV形式のファイルに出力する。
+    ///
+    /// GRIB2ファイルを正確に読み込みできたか確認するために、処理の最後で第8節を読み込み、
+    /// "7777"を読み込めるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - ファイルに記録されている順番を示す、0から始まるGRIB2報のインデックス。
+    /// * `path` - 変換後のデータを記録するCSV形式のファイルのパス。
+    /// * `boundary` - CSVファイルに出力する格子点の境界。
+    pub fn convert_message<P: AsRef<Path>>(
+        &self,
+        index: usize,
+        path: P,
+        boundary: Boundary,
+    ) -> anyhow::Result<()> {
+        let message = self.message(index)?;
         // CSVファイルを作成して、ヘッダを出力
         let file = OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(path.as_ref())?;
         let mut writer = BufWriter::new(file);
         // ヘッダ出力
         if self.with_header {
-            writeln!(writer, "longitude,latitude,value")?;
-        }
-
-        // 第7節を読み込み、ランレングス圧縮オクテット列の直前まで読み込み
-        let mut reader = self.reader.borrow_mut();
-        // 節の長さ: 4bytes
-        let section_bytes = read_u32(&mut reader)?;
-        // 節番号
-        let section_number = read_u8(&mut reader)?;
-        if section_number != 7 {
-            return Err(anyhow!(
-                "failed to read for the wrong section number(expected:7, read:{section_number}"
-            ));
-        }
-        // ランレングス圧縮オクテット列のバイト数を計算
-        // ランレングス圧縮を展開するための情報を精霊
-        let maxv = self.section5.max_level_at_file;
-        let nbit = self.section5.bits_per_data;
-        let lngu = 2u16.pow(nbit as u32) - 1 - maxv;
-        // ランレングス圧縮オクテットを展開して、CSVファイルに書き込み
-        let mut run_length = Vec::new();
-        let mut longitude = self.section3.westernmost;
-        let mut latitude = self.section3.northernmost;
-        let mut number_of_read = 0u32; // 読み込んだ格子点の数
-        for _ in 0..section_bytes - (4 + 1) {
-            let value = (read_u8(&mut reader)?) as u16;
-            if value <= maxv && !run_length.is_empty() {
-                // ランレングス符号を展開
-                let (level, count) = expand_run_length(&run_length, maxv, lngu);
-                number_of_read += count;
-                // レベル値を物理値に変換して書き込み
-                self.output_values(
-                    &mut writer,
-                    level,
-                    count,
-                    &mut longitude,
-                    &mut latitude,
-                    &boundary,
-                )?;
-                run_length.clear();
+            if self.mesh_level.is_some() {
+                writeln!(writer, "longitude,latitude,value,mesh_code")?;
+            } else {
+                writeln!(writer, "longitude,latitude,value")?;
             }
-            run_length.push(value);
         }
-        if !run_length.is_empty() {
-            let (level, count) = expand_run_length(&run_length, maxv, lngu);
-            number_of_read += count;
-            self.output_values(
+
+        // 資料値を展開して、CSVファイルに書き込み
+        let mut index = 0u32; // 次に書き込む格子点の、資料の並び順でのインデックス
+        self.read_values(message, |value, count| {
+            message.output_values(
                 &mut writer,
-                level,
+                value,
                 count,
-                &mut longitude,
-                &mut latitude,
+                &mut index,
                 &boundary,
+                self.mesh_level,
+            )
+        })?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// ファイルの最初のGRIB2報の第7節を読み込んで、領域ごとの物理値の統計量をCSV形式のファイルに出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 統計量を記録するCSV形式のファイルのパス。
+    /// * `zones` - 統計量を集計する領域。
+    /// * `mode` - 格子点を領域に含める条件。
+    pub fn convert_zonal_statistics<P: AsRef<Path>>(
+        &self,
+        path: P,
+        zones: &[Zone],
+        mode: AreaMode,
+    ) -> anyhow::Result<()> {
+        self.convert_zonal_statistics_message(0, path, zones, mode)
+    }
+
+    /// 指定されたGRIB2報の第7節を読み込んで、領域ごとの物理値の統計量をCSV形式のファイルに出力する。
+    ///
+    /// 領域ごとに、領域の識別子、統計量に含めた格子点の数、物理値の平均、最小値、最大値、
+    /// 格子の面積で重み付けした平均、及び物理値が0より大きい格子が占める面積の比率を1行に出力する。
+    /// 統計量に含める格子点がない領域の統計量は、空欄で出力する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - ファイルに記録されている順番を示す、0から始まるGRIB2報のインデックス。
+    /// * `path` - 統計量を記録するCSV形式のファイルのパス。
+    /// * `zones` - 統計量を集計する領域。
+    /// * `mode` - 格子点を領域に含める条件。
+    pub fn convert_zonal_statistics_message<P: AsRef<Path>>(
+        &self,
+        index: usize,
+        path: P,
+        zones: &[Zone],
+        mode: AreaMode,
+    ) -> anyhow::Result<()> {
+        let statistics = self.zonal_statistics_message(index, zones, mode)?;
+        let decimal_places = self.message(index)?.metadata.section5.decimal_places();
+        // CSVファイルを作成して、ヘッダを出力
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path.as_ref())?;
+        let mut writer = BufWriter::new(file);
+        if self.with_header {
+            writeln!(writer, "id,cells,mean,min,max,weighted_mean,rainy_coverage")?;
+        }
+
+        // 領域ごとの統計量を書き込み
+        let format = |value: Option<f64>, decimal_places: usize| {
+            value
+                .map(|value| format!("{value:.decimal_places$}"))
+                .unwrap_or_default()
+        };
+        for zone in statistics {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                csv_field(&zone.id),
+                zone.cells,
+                format(zone.mean, 6),
+                format(zone.min, decimal_places),
+                format(zone.max, decimal_places),
+                format(zone.weighted_mean, 6),
+                format(zone.rainy_coverage, 6),
             )?;
         }
         writer.flush()?;
-        if number_of_read != self.section3.number_of_points {
+
+        Ok(())
+    }
+
+    /// 最初のGRIB2報の第7節を読み込んで、領域ごとの物理値の統計量を返却する。
+    pub fn zonal_statistics(
+        &self,
+        zones: &[Zone],
+        mode: AreaMode,
+    ) -> anyhow::Result<Vec<ZonalStatistics>> {
+        self.zonal_statistics_message(0, zones, mode)
+    }
+
+    /// 指定されたGRIB2報の第7節を読み込んで、領域ごとの物理値の統計量を返却する。
+    ///
+    /// GRIB2ファイルを正確に読み込みできたか確認するために、処理の最後で第8節を読み込み、
+    /// "7777"を読み込めるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - ファイルに記録されている順番を示す、0から始まるGRIB2報のインデックス。
+    /// * `zones` - 統計量を集計する領域。
+    /// * `mode` - 格子点を領域に含める条件。
+    pub fn zonal_statistics_message(
+        &self,
+        index: usize,
+        zones: &[Zone],
+        mode: AreaMode,
+    ) -> anyhow::Result<Vec<ZonalStatistics>> {
+        let message = self.message(index)?;
+        let section3 = &message.metadata.section3;
+        let mut accumulator = ZonalAccumulator::new(zones, mode);
+        let mut index = 0u32; // 次に集計する格子点の、資料の並び順でのインデックス
+        self.read_values(message, |value, count| {
+            // 格子点数を超える格子点は集計しない
+            section3.check_point_range(index, count)?;
+            if let Some(value) = value {
+                for index in index..index + count {
+                    accumulator.add(section3, index, value);
+                }
+            }
+            index += count;
+            Ok(())
+        })?;
+
+        Ok(accumulator.finish())
+    }
+
+    /// 最初のGRIB2報の第7節を読み込んで、格子点の物理値を返却する。
+    pub fn grid(&self) -> anyhow::Result<Grid> {
+        self.grid_message(0)
+    }
+
+    /// 指定されたGRIB2報の第7節を読み込んで、格子点の物理値を返却する。
+    ///
+    /// GRIB2ファイルを正確に読み込みできたか確認するために、処理の最後で第8節を読み込み、
+    /// "7777"を読み込めるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - ファイルに記録されている順番を示す、0から始まるGRIB2報のインデックス。
+    pub fn grid_message(&self, index: usize) -> anyhow::Result<Grid> {
+        let message = self.message(index)?;
+        let mut grid = Grid::new(message.metadata.section3.clone())?;
+        let mut index = 0u32; // 次に設定する格子点の、資料の並び順でのインデックス
+        self.read_values(message, |value, count| {
+            grid.set_values(index, value, count)?;
+            index += count;
+            Ok(())
+        })?;
+
+        Ok(grid)
+    }
+
+    /// 最初のGRIB2報の第7節を展開しながら、格子点を返却するイテレーターを返却する。
+    pub fn points(&self) -> anyhow::Result<GridPoints<'_, R>> {
+        self.points_message(0)
+    }
+
+    /// 指定されたGRIB2報の第7節を展開しながら、格子点を資料の並び順で返却するイテレーターを返却する。
+    ///
+    /// イテレーターは欠測値の格子点も返却する。
+    /// イテレーターが存在する間、他のGRIB2報の変換や格子点のイテレーターの作成はエラーになる。
+    ///
+    /// # 引数
+    ///
+    /// * `index` - ファイルに記録されている順番を示す、0から始まるGRIB2報のインデックス。
+    pub fn points_message(&self, index: usize) -> anyhow::Result<GridPoints<'_, R>> {
+        let message = self.message(index)?;
+
+        GridPoints::new(message, self.borrow_reader()?)
+    }
+
+    /// 指定されたインデックスのGRIB2報を返却する。
+    fn message(&self, index: usize) -> anyhow::Result<&Grib2Message> {
+        self.messages.get(index).ok_or_else(|| {
+            anyhow!(
+                "the message index is out of range (index:{index}, number of messages:{})",
+                self.messages.len()
+            )
+        })
+    }
+
+    /// GRIB2報の第7節の資料値を展開して、物理値を関数に渡した後、第8節を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `message` - GRIB2報。
+    /// * `f` - 資料の並び順で展開した物理値と、その物理値が連続する格子点の数を受け取る関数。
+    ///   欠測値またはビットマップで除外された格子点の場合、物理値は`None`。
+    fn read_values<F>(&self, message: &Grib2Message, f: F) -> anyhow::Result<()>
+    where
+        F: FnMut(Option<f64>, u32) -> anyhow::Result<()>,
+    {
+        let mut reader = self.borrow_reader()?;
+        let reader = &mut *reader;
+        // 第6節のビットマップと第7節の資料値をまとめて読み込み
+        let (bitmap, data) = message.read_section7(reader)?;
+        // 資料値を展開
+        let number_of_points = message.metadata.section3.number_of_points;
+        let number_of_read = message.decode_section7(&data, bitmap.as_deref(), f)?;
+        if number_of_read != number_of_points {
             return Err(anyhow!(
                 "failed to read points (expected:{}, read:{})",
-                self.section3.number_of_points,
+                number_of_points,
                 number_of_read
             ));
         }
-        // 第8節を読み込み
-        read_section8(&mut reader)?;
 
-        Ok(())
+        message.read_message_end(reader)
+    }
+
+    /// GRIB2ファイルのリーダーを借用する。
+    ///
+    /// 格子点のイテレーターがリーダーを借用している場合はエラーを返却する。
+    fn borrow_reader(&self) -> anyhow::Result<RefMut<'_, R>> {
+        self.reader
+            .try_borrow_mut()
+            .map_err(|_| anyhow!("the reader is being used by a grid point iterator"))
+    }
+}
+
+impl Grib2Message {
+    /// 第6節のビットマップを読み込んだ後、第7節の資料値をまとめて読み込む。
+    ///
+    /// 資料値を1オクテットずつリーダーから読み込まないように、資料値全体をメモリに読み込む。
+    /// 関数終了後、ファイルポインタは第8節の開始位置に移動する。
+    ///
+    /// # 戻り値
+    ///
+    /// 適用するビットマップと、第7節の資料値。
+    fn read_section7<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+    ) -> anyhow::Result<(Option<Vec<u8>>, Vec<u8>)> {
+        // 第6節のビットマップを読み込み
+        let bitmap = match &self.bitmap {
+            Some(bitmap) => Some(bitmap.read(reader)?),
+            None => None,
+        };
+        // 第8節の開始位置と入力の末尾の内、手前の位置を第7節の末尾の上限とする
+        let input_length = reader.seek(SeekFrom::End(0))?;
+        let message_end = self.offset + self.metadata.section0.total_length - 4;
+        let limit = message_end.min(input_length);
+        reader.seek(SeekFrom::Start(self.section7_offset))?;
+        // 節の長さ: 4bytes
+        let section_bytes = read_u32(reader)?;
+        // 節番号
+        let section_number = read_u8(reader)?;
+        if section_number != 7 {
+            return Err(anyhow!(
+                "failed to read for the wrong section number(expected:7, read:{section_number}"
+            ));
+        }
+        // 資料値（末尾の詰め物を含む）
+        let length = section_bytes
+            .checked_sub(4 + 1)
+            .ok_or_else(|| anyhow!("the length of section 7 is too short ({section_bytes})"))?;
+        // 記録されている節の長さを信用せず、資料値を読み込むバッファを確保する前に確認
+        if limit < self.section7_offset + section_bytes as u64 {
+            return Err(anyhow!(
+                "the length of section 7 exceeds the end of the message ({section_bytes})"
+            ));
+        }
+        let mut data = vec![0; length as usize];
+        reader
+            .read_exact(&mut data)
+            .map_err(|_| anyhow!("failed to read the data values in section 7"))?;
+
+        Ok((bitmap, data))
+    }
+
+    /// GRIB2報の末尾に移動して、第8節を読み込む。
+    ///
+    /// 第4節から第7節が繰り返し記録されている場合は、最後の第7節の後に記録されている第8節を読み込む。
+    fn read_message_end<R: Read + Seek>(&self, reader: &mut R) -> anyhow::Result<()> {
+        let total_length = self.metadata.section0.total_length;
+        reader.seek(SeekFrom::Start(self.offset + total_length - 4))?;
+
+        read_section8(reader)
+    }
+
+    /// 第7節の資料値を、第5節の資料表現テンプレートと第6節のビットマップに従って展開する。
+    ///
+    /// # 引数
+    ///
+    /// * `data` - 第7節の資料値。
+    /// * `bitmap` - 第6節のビットマップ。ビットマップを適用しない場合は`None`。
+    /// * `f` - 展開した物理値と、その物理値が連続する格子点の数を受け取る関数。
+    ///   欠測値またはビットマップで除外された格子点の場合、物理値は`None`。
+    ///
+    /// # 戻り値
+    ///
+    /// 展開した格子点の数。
+    fn decode_section7<F>(
+        &self,
+        data: &[u8],
+        bitmap: Option<&[u8]>,
+        mut f: F,
+    ) -> anyhow::Result<u32>
+    where
+        F: FnMut(Option<f64>, u32) -> anyhow::Result<()>,
+    {
+        let Some(bitmap) = bitmap else {
+            return self.decode_values(data, f);
+        };
+        // ビットマップのビットが1の格子点に、資料値を割り当てる
+        let mut expander = BitmapExpander::new(bitmap, self.metadata.section3.number_of_points);
+        let number_of_values =
+            self.decode_values(data, |value, count| expander.expand(value, count, &mut f))?;
+        if number_of_values != self.metadata.section5.number_of_points {
+            return Err(anyhow!(
+                "failed to read values (expected:{}, read:{number_of_values})",
+                self.metadata.section5.number_of_points
+            ));
+        }
+
+        expander.finish(&mut f)
     }
 
+    /// 第7節の資料値を、第5節の資料表現テンプレートに従って展開する。
+    ///
+    /// # 引数
+    ///
+    /// * `data` - 第7節の資料値。
+    /// * `f` - 展開した物理値と、その物理値が連続する資料値の数を受け取る関数。
+    ///   欠測値の場合、物理値は`None`。
+    ///
+    /// # 戻り値
+    ///
+    /// 展開した資料値の数。
+    fn decode_values<F>(&self, data: &[u8], mut f: F) -> anyhow::Result<u32>
+    where
+        F: FnMut(Option<f64>, u32) -> anyhow::Result<()>,
+    {
+        let mut decoder = ValueDecoder::new(&self.metadata.section5, data)?;
+        let mut bits = BitReader::new(data);
+        let mut number_of_values = 0u32;
+        while let Some(run) = decoder.next(&mut bits)? {
+            f(run.value, run.count)?;
+            number_of_values += run.count;
+        }
+
+        Ok(number_of_values)
+    }
+
+    /// 物理値をCSVファイルに書き込む。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - CSVファイル・ライター。
+    /// * `value` - 物理値。欠測値の場合は`None`。
+    /// * `count` - 物理値が連続する格子点の数。
+    /// * `index` - 現在の格子の、資料の並び順でのインデックス。
+    /// * `boundary` - CSVファイルに出力する格子点の境界。
+    /// * `mesh_level` - 出力する地域メッシュ・コードの区画の種類。
     fn output_values(
         &self,
         writer: &mut FileWriter,
-        level: u16,
+        value: Option<f64>,
         count: u32,
-        longitude: &mut u32,
-        latitude: &mut u32,
+        index: &mut u32,
         boundary: &Boundary,
+        mesh_level: Option<MeshLevel>,
     ) -> anyhow::Result<()> {
-        if 0 < level {
+        let section3 = &self.metadata.section3;
+        // 格子点数を超える格子点の行を書き込まないように、書き込む前に確認
+        section3.check_point_range(*index, count)?;
+        if let Some(value) = value {
+            let decimal_places = self.metadata.section5.decimal_places();
             for _ in 0..count {
-                if boundary.contains(*longitude, *latitude) {
-                    writeln!(
+                let (longitude, latitude) = section3.coordinate(*index);
+                if boundary.contains_point(section3, *index, longitude, latitude) {
+                    write!(
                         writer,
-                        "{:.6},{:.6},{}",
-                        (*longitude as f64) / 1_000_000f64,
-                        (*latitude as f64) / 1_000_000f64,
-                        self.section5.level_values[(level - 1) as usize],
+                        "{:.6},{:.6},{:.*}",
+                        (longitude as f64) / 1_000_000f64,
+                        (latitude as f64) / 1_000_000f64,
+                        decimal_places,
+                        value,
                     )?;
+                    if let Some(level) = mesh_level {
+                        match mesh_code(longitude, latitude, level) {
+                            Some(code) => {
+                                write!(writer, ",{:0width$}", code, width = level.digits())?
+                            }
+                            None => write!(writer, ",")?,
+                        }
+                    }
+                    writeln!(writer)?;
                 }
-                *longitude += self.section3.longitude_increment;
-                if self.section3.easternmost < *longitude {
-                    *longitude = self.section3.westernmost;
-                    *latitude -= self.section3.latitude_increment;
-                }
+                *index += 1;
             }
         } else {
-            // レベル0は、欠測値であるため、出力しない
-            (*longitude, *latitude) = move_lattice_for_missing_values(
-                *longitude,
-                *latitude,
-                count,
-                self.section3.longitude_increment,
-                self.section3.latitude_increment,
-                self.section3.westernmost,
-                self.section3.easternmost,
-            );
+            // 欠測値は出力しないため、格子を移動するのみ
+            *index += count;
         }
 
         Ok(())
     }
 }
 
-/// 欠測値のときに、格子を移動する。
+/// ファイルに記録されているGRIB2報を、先頭から順番に読み込むイテレーター
 ///
-/// # 引数
+/// 第0節に記録されているGRIB報全体の長さを利用して、次のGRIB2報の開始位置に移動する。
+/// 1つのGRIB2報に第4節から第7節が繰り返し記録されている場合は、繰り返しごとに資料を返却する。
+struct MessageReader<'a, R> {
+    reader: &'a mut R,
+    /// GRIB2報の検証方針
+    policy: ValidationPolicy,
+    /// ファイルの先頭から次に読み込むGRIB2報の先頭までのバイト数
+    offset: u64,
+    /// 読み込んだGRIB2報に記録されている資料の内、まだ返却していない資料
+    fields: VecDeque<Grib2Message>,
+    /// 読み込みに失敗したかを示すフラグ
+    failed: bool,
+}
+
+impl<'a, R: BufRead + Seek> MessageReader<'a, R> {
+    fn new(reader: &'a mut R, policy: ValidationPolicy) -> Self {
+        Self {
+            reader,
+            policy,
+            offset: 0,
+            fields: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// ファイル・ポインタの位置から、GRIB2報を第8節まで読み込んで、GRIB2報に記録されている資料を返却する。
+    ///
+    /// 第4節から第7節（第3節を含む場合がある）が繰り返し記録されている場合は、繰り返しごとに資料を返却する。
+    /// 第2節（地域使用節）は読み飛ばし、第7節は資料値を読み込まずに位置のみを記録する。
+    /// 関数終了後、ファイル・ポインタは次のGRIB2報の開始位置に移動する。
+    fn read_message(&mut self) -> anyhow::Result<Vec<Grib2Message>> {
+        let reader = &mut *self.reader;
+        let offset = self.offset;
+        reader.seek(SeekFrom::Start(offset))?;
+        // 第0節を読み込み
+        let section0 = read_section0(reader)?;
+        // 第8節の開始位置
+        let end = offset
+            .checked_add(section0.total_length)
+            .and_then(|end| end.checked_sub(4))
+            .filter(|&end| offset + 16 <= end)
+            .ok_or_else(|| anyhow!("the total length is too short ({})", section0.total_length))?;
+        // 第1節を読み込み
+        expect_section(reader, end, 1)?;
+        let section1 = read_section1(reader, &self.policy)?;
+        // 第8節に達するまで、第2節から第7節を読み込み
+        // ビットマップ指示符254で適用するビットマップは、同じGRIB2報で以前に定義されたビットマップ
+        let mut section3 = None;
+        let mut previous_bitmap = None;
+        let mut fields = vec![];
+        while let Some(section_number) = peek_section_number(reader, end)? {
+            match section_number {
+                // 第2節は読み飛ばす
+                2 => skip_section(reader)?,
+                3 => section3 = Some(read_section3(reader)?),
+                4 => {
+                    let section3 = section3
+                        .clone()
+                        .ok_or_else(|| anyhow!("section 3 does not precede section 4"))?;
+                    // 第4節を読み込み
+                    let section4 = read_section4(reader)?;
+                    // 第5節を読み込み
+                    expect_section(reader, end, 5)?;
+                    let section5 = read_section5(reader)?;
+                    // 第6節を読み込み
+                    expect_section(reader, end, 6)?;
+                    let (section6, bitmap) =
+                        read_section6(reader, section3.number_of_points, previous_bitmap)?;
+                    if bitmap.is_some() {
+                        previous_bitmap = bitmap;
+                    }
+                    // ビットマップを適用する場合は、ビットが1の格子点の数が資料点数
+                    let number_of_values =
+                        bitmap.map_or(section3.number_of_points, |b| b.number_of_values);
+                    if number_of_values != section5.number_of_points {
+                        return Err(anyhow!(
+                            "the number of points is different (section3 or bitmap:{}, section5:{})",
+                            number_of_values,
+                            section5.number_of_points
+                        ));
+                    }
+                    // 第7節の位置を記録して、読み飛ばす
+                    expect_section(reader, end, 7)?;
+                    let section7_offset = reader.stream_position()?;
+                    skip_section(reader)?;
+                    fields.push(Grib2Message {
+                        offset,
+                        metadata: Grib2Metadata {
+                            section0: section0.clone(),
+                            section1: section1.clone(),
+                            section3,
+                            section4,
+                            section5,
+                            section6,
+                        },
+                        bitmap,
+                        section7_offset,
+                    });
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "section {section_number} is not expected after section 1, 2, 3 or 7"
+                    ))
+                }
+            }
+        }
+        // 第8節を読み込み
+        read_section8(reader)?;
+        if fields.is_empty() {
+            return Err(anyhow!("the message does not contain any data"));
+        }
+        // 次のGRIB2報の開始位置
+        self.offset = end + 4;
+
+        Ok(fields)
+    }
+}
+
+impl<R: BufRead + Seek> Iterator for MessageReader<'_, R> {
+    type Item = anyhow::Result<Grib2Message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // 読み込んだGRIB2報に、まだ返却していない資料がある場合は返却
+        if let Some(field) = self.fields.pop_front() {
+            return Some(Ok(field));
+        }
+        if self.failed {
+            return None;
+        }
+        // ファイルの終端に達した場合は終了
+        match self.reader.fill_buf() {
+            Ok([]) => return None,
+            Ok(_) => {}
+            Err(e) => {
+                self.failed = true;
+                return Some(Err(e.into()));
+            }
+        }
+        match self.read_message() {
+            Ok(fields) => {
+                self.fields = fields.into();
+                self.fields.pop_front().map(Ok)
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(anyhow!(
+                    "failed to read a message at offset {}: {e}",
+                    self.offset
+                )))
+            }
+        }
+    }
+}
+
+/// ファイル・ポインタの位置にある節の節番号を、ファイル・ポインタを移動せずに返却する。
 ///
-/// * `longitude` - 現在の格子の経度。
-/// * `latitude` - 現在の格子の緯度。
-/// * `count` - 格子のレベル値が連続する数。
-/// * `longitude_increment` - 経線方向の格子の移動量。
-/// * `latitude_increment` - 緯線方向の格子の移動量。
-/// * `lattice_width` - 経線方向の格子の幅。
-/// * `westernmost` - 最西端の経度。
-/// * `easternmost` - 最東端の経度。
+/// 節の長さを確認して、節が第8節の開始位置を超える場合はエラーを返却する。
+/// ファイル・ポインタが第8節の開始位置にある場合は`None`を返却する。
 ///
-/// # 戻り値
+/// # 引数
 ///
-/// 移動後の格子の経度と緯度のタプル。
-fn move_lattice_for_missing_values(
-    longitude: u32,
-    latitude: u32,
-    count: u32,
-    longitude_increment: u32,
-    latitude_increment: u32,
-    westernmost: u32,
-    easternmost: u32,
-) -> (u32, u32) {
-    let mut longitude = longitude;
-    let mut latitude = latitude;
-    let lattice_width = easternmost - westernmost;
-    // 格子を経線方向に移動する合計の度数
-    let sum_of_lon_inc = longitude_increment as u64 * count as u64;
-    // 格子を緯線方向に移動する格子数
-    let lat_inc_times = sum_of_lon_inc / lattice_width as u64;
-    // 緯線方向に格子を移動
-    latitude -= latitude_increment * lat_inc_times as u32;
-    // 経線方向に格子を移動
-    // 格子が最東端に達したとき、次の格子は最西端かつ緯線南方向に1格子移動する。
-    // このとき、経線方向に格子分移動しないため、緯線方向に移動する回数だけ、経線方向の移動を無効にする。
-    // よって、`- (longitude_increment * lat_inc_times as u32)`している。
-    longitude += ((sum_of_lon_inc % lattice_width as u64)
-        - (longitude_increment as u64 * lat_inc_times)) as u32;
-    if easternmost < longitude {
-        // 上記と同様な理由で、`- longitude_increment`している。
-        longitude = westernmost + (longitude - easternmost - longitude_increment);
-        latitude -= latitude_increment;
-    }
-
-    (longitude, latitude)
-}
-
-/// ファイルから1バイト読み込み、u8型の値として返却する。
-fn read_u8(reader: &mut FileReader) -> anyhow::Result<u8> {
+/// * `reader` - リーダー。
+/// * `end` - ファイルの先頭から第8節の開始位置までのバイト数。
+fn peek_section_number<R: Read + Seek>(reader: &mut R, end: u64) -> anyhow::Result<Option<u8>> {
+    let start = reader.stream_position()?;
+    if start == end {
+        return Ok(None);
+    }
+    // 節の長さ
+    let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of a section"))?;
+    // 節番号
+    let section_number = read_u8(reader).map_err(|_| anyhow!("failed to read a section number"))?;
+    if length < 4 + 1 || end < start + length as u64 {
+        return Err(anyhow!(
+            "the length of section {section_number} is out of the message (length:{length})"
+        ));
+    }
+    reader.seek(SeekFrom::Start(start))?;
+
+    Ok(Some(section_number))
+}
+
+/// ファイル・ポインタの位置にある節が、指定された節番号の節であることを確認する。
+fn expect_section<R: Read + Seek>(reader: &mut R, end: u64, number: u8) -> anyhow::Result<()> {
+    match peek_section_number(reader, end)? {
+        Some(section_number) if section_number == number => Ok(()),
+        Some(section_number) => Err(anyhow!(
+            "section {number} is expected, but section {section_number} is found"
+        )),
+        None => Err(anyhow!(
+            "section {number} is expected, but section 8 is found"
+        )),
+    }
+}
+
+/// ファイル・ポインタの位置にある節を読み飛ばす。
+fn skip_section<R: Read + Seek>(reader: &mut R) -> anyhow::Result<()> {
+    let start = reader.stream_position()?;
+    let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of a section"))?;
+    reader.seek(SeekFrom::Start(start + length as u64))?;
+
+    Ok(())
+}
+
+/// リーダーから1バイト読み込み、u8型の値として返却する。
+fn read_u8<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let mut buf = [0; 1];
     let size = reader.read(&mut buf)?;
     if size != 1 {
@@ -360,8 +1166,8 @@ fn read_u8(reader: &mut FileReader) -> anyhow::Result<u8> {
     Ok(u8::from_be_bytes(buf))
 }
 
-/// ファイルから2バイト読み込み、u16型の値として返却する。
-fn read_u16(reader: &mut FileReader) -> anyhow::Result<u16> {
+/// リーダーから2バイト読み込み、u16型の値として返却する。
+fn read_u16<R: Read>(reader: &mut R) -> anyhow::Result<u16> {
     let mut buf = [0; 2];
     let size = reader.read(&mut buf)?;
     if size != 2 {
@@ -371,8 +1177,8 @@ fn read_u16(reader: &mut FileReader) -> anyhow::Result<u16> {
     Ok(u16::from_be_bytes(buf))
 }
 
-/// ファイルから4バイト読み込み、u32型の値として返却する。
-fn read_u32(reader: &mut FileReader) -> anyhow::Result<u32> {
+/// リーダーから4バイト読み込み、u32型の値として返却する。
+fn read_u32<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
     let mut buf = [0; 4];
     let size = reader.read(&mut buf)?;
     if size != 4 {
@@ -382,26 +1188,53 @@ fn read_u32(reader: &mut FileReader) -> anyhow::Result<u32> {
     Ok(u32::from_be_bytes(buf))
 }
 
+/// リーダーから8バイト読み込み、u64型の値として返却する。
+fn read_u64<R: Read>(reader: &mut R) -> anyhow::Result<u64> {
+    let mut buf = [0; 8];
+    let size = reader.read(&mut buf)?;
+    if size != 8 {
+        return Err(anyhow!("failed to read a u64 value"));
+    }
+
+    Ok(u64::from_be_bytes(buf))
+}
+
+/// 第0節情報
+#[derive(Debug, Clone)]
+pub struct Section0 {
+    /// 資料分野（符号表0.0）
+    pub document_domain: u8,
+    /// GRIB版番号
+    pub grib_version: u8,
+    /// GRIB報全体の長さ
+    pub total_length: u64,
+}
+
 /// 第0節を読み込み、内容を確認する。
 ///
-/// ファイル・ポインタが、ファイルの先頭にあることを想定している。
+/// ファイル・ポインタが、GRIB2報の先頭にあることを想定している。
 /// 関数終了後、ファイル・ポインタは第1節の開始位置に移動する。
-fn read_section0(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section0<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Section0> {
     // GRIB
     read_section0_grib(reader)?;
     // 保留: 2bytes
     reader.seek_relative(2)?;
     // 資料分野
-    read_section0_document_domain(reader)?;
+    let document_domain = read_section0_document_domain(reader)?;
     // GRIB反番号
-    read_section0_grib_version(reader)?;
-
+    let grib_version = read_section0_grib_version(reader)?;
     // GRIB報全体の長さ
-    reader.seek_relative(8).map_err(|e| e.into())
+    let total_length = read_section0_total_length(reader)?;
+
+    Ok(Section0 {
+        document_domain,
+        grib_version,
+        total_length,
+    })
 }
 
 /// 第0節 GRIBを読み込んで、"GRIB"が記録されているか確認する。
-fn read_section0_grib(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section0_grib<R: Read>(reader: &mut R) -> anyhow::Result<()> {
     let mut buf = [0; 4];
 
     let size = reader.read(&mut buf)?;
@@ -416,62 +1249,105 @@ fn read_section0_grib(reader: &mut FileReader) -> anyhow::Result<()> {
 }
 
 /// 第0節 資料分野を読み込んで、想定している資料分野であるか確認する。
-fn read_section0_document_domain(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section0_document_domain<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a document domain"))?;
     match value {
-        DOCUMENT_DOMAIN => Ok(()),
+        DOCUMENT_DOMAIN => Ok(value),
         _ => Err(anyhow!("a document domain is not {DOCUMENT_DOMAIN}")),
     }
 }
 
 /// 第0節 GRIB版番号を読み込んで、想定しているGRIB版番号であるか確認する。
-fn read_section0_grib_version(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section0_grib_version<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a grib version"))?;
     match value {
-        GRIB_VERSION => Ok(()),
+        GRIB_VERSION => Ok(value),
         _ => Err(anyhow!("a grib version is not {GRIB_VERSION}")),
     }
 }
 
-/// 第1節を読み込んで、確認する。
+/// 第0節 GRIB報全体の長さを読み込んで、返却する。
+fn read_section0_total_length<R: Read>(reader: &mut R) -> anyhow::Result<u64> {
+    read_u64(reader).map_err(|_| anyhow!("failed to read a total length of the message"))
+}
+
+/// 第1節情報
+#[derive(Debug, Clone)]
+pub struct Section1 {
+    /// 作成中枢の識別（共通符号表C-11）
+    pub center: u16,
+    /// 作成副中枢
+    pub sub_center: u16,
+    /// GRIBマスター表バージョン番号
+    pub master_table_version: u8,
+    /// GRIB地域表バージョン番号
+    pub local_table_version: u8,
+    /// 参照時刻の意味（符号表1.2）
+    pub reference_time_significance: u8,
+    /// 資料の参照時刻
+    pub referenced_at: PrimitiveDateTime,
+    /// 作成ステータス（符号表1.3）
+    pub creation_status: u8,
+    /// 資料の種類（符号表1.4）
+    pub document_kind: u8,
+}
+
+/// 第1節を読み込んで、第1節の情報を返却する。
 ///
 /// ファイルポインタが、第1節の開始位置にあることを想定している。
 /// 関数終了後、ファイルポインタは第3節の開始位置に移動する。
 /// なお、実装時点で、第2節は省略されている。
-fn read_section1(reader: &mut FileReader) -> anyhow::Result<()> {
-    // 節の長さ: 4bytes
-    reader.seek_relative(4)?;
+fn read_section1<R: Read + Seek>(
+    reader: &mut R,
+    policy: &ValidationPolicy,
+) -> anyhow::Result<Section1> {
+    let start = reader.stream_position()?;
+    // 節の長さ
+    let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of section 1"))?;
     // 節番号
     let section_number =
         read_u8(reader).map_err(|_| anyhow!("failed to read section number at section 1"))?;
     if section_number != 1 {
         return Err(anyhow!("section number is miss match in section 1"));
     }
-    // 作成中枢の識別: 2bytes
-    // 作成副中枢: 2bytes
-    reader.seek_relative(4)?;
+    // 作成中枢の識別
+    let center = read_u16(reader).map_err(|_| anyhow!("failed to read a center"))?;
+    // 作成副中枢
+    let sub_center = read_u16(reader).map_err(|_| anyhow!("failed to read a sub center"))?;
     // GRIBマスター表バージョン番号
-    read_section1_grib_master_table_version(reader)?;
+    let master_table_version = read_section1_grib_master_table_version(reader)?;
     // GRIB地域表バージョン番号
-    read_section1_grib_local_table_version(reader)?;
-    // 参照時刻の意味: 1byte
-    reader.seek_relative(1)?;
+    let local_table_version = read_section1_grib_local_table_version(reader)?;
+    // 参照時刻の意味
+    let reference_time_significance =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a significance of reference time"))?;
     // 資料の参照時刻（日時）
-    read_section1_referenced_at(reader)?;
+    let referenced_at = read_section1_referenced_at(reader)?;
     // 作成ステータス
-    read_section1_creation_status(reader)?;
+    let creation_status = read_section1_creation_status(reader, policy.accept_test_products)?;
     // 資料の種類
-    read_section1_document_kind(reader)?;
+    let document_kind = read_section1_document_kind(reader, policy.accept_any_document_kinds)?;
+    // 節の残りをスキップ
+    reader.seek(SeekFrom::Start(start + length as u64))?;
 
-    Ok(())
+    Ok(Section1 {
+        center,
+        sub_center,
+        master_table_version,
+        local_table_version,
+        reference_time_significance,
+        referenced_at,
+        creation_status,
+        document_kind,
+    })
 }
 
 /// 第１節 GRIBマスター表バージョン番号を読み込んで、想定しているGRIBマスター表バージョン番号であるか確認する。
-fn read_section1_grib_master_table_version(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section1_grib_master_table_version<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value =
         read_u8(reader).map_err(|_| anyhow!("failed to read a grib master table version"))?;
     match value {
-        GRIB_MASTER_TABLE_VERSION => Ok(()),
+        GRIB_MASTER_TABLE_VERSION => Ok(value),
         _ => Err(anyhow!(
             "a grib master table version is not {GRIB_MASTER_TABLE_VERSION}"
         )),
@@ -479,11 +1355,11 @@ fn read_section1_grib_master_table_version(reader: &mut FileReader) -> anyhow::R
 }
 
 /// 第１節 GRIB地域差バージョン番号を読み込んで、想定しているGRIB地域差バージョン番号であるか確認する。
-fn read_section1_grib_local_table_version(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section1_grib_local_table_version<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value =
         read_u8(reader).map_err(|_| anyhow!("failed to read a grib local table version"))?;
     match value {
-        GRIB_LOCAL_TABLE_VERSION => Ok(()),
+        GRIB_LOCAL_TABLE_VERSION => Ok(value),
         _ => Err(anyhow!(
             "a grib local table version is not {GRIB_LOCAL_TABLE_VERSION}"
         )),
@@ -491,14 +1367,18 @@ fn read_section1_grib_local_table_version(reader: &mut FileReader) -> anyhow::Re
 }
 
 /// 第１節 資料の参照日時を読み込んで返却する。
-fn read_section1_referenced_at(reader: &mut FileReader) -> anyhow::Result<PrimitiveDateTime> {
-    // 資料の参照時刻（年）
-    let year = read_u16(reader).map_err(|_| anyhow!("failed to read a reference year"))?;
-    // 資料の参照時刻（月以降）
+fn read_section1_referenced_at<R: Read>(reader: &mut R) -> anyhow::Result<PrimitiveDateTime> {
+    read_date_time(reader).map_err(|e| anyhow!("failed to read a reference time: {e}"))
+}
+
+/// 年（2bytes）、月、日、時、分及び秒（各1byte）の順で記録された日時を読み込んで返却する。
+fn read_date_time<R: Read>(reader: &mut R) -> anyhow::Result<PrimitiveDateTime> {
+    // 年
+    let year = read_u16(reader).map_err(|_| anyhow!("failed to read a year"))?;
+    // 月以降
     let mut parts = Vec::new();
     for _ in 0..5 {
-        let value =
-            read_u8(reader).map_err(|_| anyhow!("failed to read for any reference time parts"))?;
+        let value = read_u8(reader).map_err(|_| anyhow!("failed to read for any time parts"))?;
         parts.push(value);
     }
     // 日付と時刻を構築
@@ -510,49 +1390,462 @@ fn read_section1_referenced_at(reader: &mut FileReader) -> anyhow::Result<Primit
 }
 
 /// 第１節 作成ステータスを読み込んで、想定している作成ステータスであるか確認する。
-fn read_section1_creation_status(reader: &mut FileReader) -> anyhow::Result<()> {
+///
+/// `accept_any`が`true`の場合は、作成ステータスを確認せずに返却する。
+fn read_section1_creation_status<R: Read>(reader: &mut R, accept_any: bool) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a creation status"))?;
     match value {
-        CREATION_STATUS => Ok(()),
+        _ if accept_any => Ok(value),
+        CREATION_STATUS => Ok(value),
         _ => Err(anyhow!("a creation status is test product")),
     }
 }
 
 /// 第１節 資料の種類を読み込んで、想定している資料の種類であるか確認する。
-fn read_section1_document_kind(reader: &mut FileReader) -> anyhow::Result<()> {
+///
+/// `accept_any`が`true`の場合は、資料の種類を確認せずに返却する。
+fn read_section1_document_kind<R: Read>(reader: &mut R, accept_any: bool) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a document kind"))?;
     match value {
-        DOCUMENT_KIND => Ok(()),
+        _ if accept_any => Ok(value),
+        DOCUMENT_KIND => Ok(value),
         _ => Err(anyhow!("a document kind is not {DOCUMENT_KIND}")),
     }
 }
 
 /// 第3節情報
-struct Section3 {
+#[derive(Debug, Clone)]
+pub struct Section3 {
     /// 資料点数
     pub number_of_points: u32,
-    /// 最初（最も左上）の格子点の緯度（10^6度単位）
-    pub northernmost: u32,
-    /// 最初（最も左上）の格子点の経度（10^6度単位）
-    pub westernmost: u32,
-    /// 最後（最も右下）の格子点の緯度（10^6度単位）
-    #[allow(dead_code)]
-    pub southernmost: u32,
-    /// 最後（最も右下）の格子点の経度（10^6度単位）
-    pub easternmost: u32,
+    /// 格子系定義テンプレート番号
+    pub template: u16,
+    /// 地球の形状
+    pub earth_shape: EarthShape,
+    /// 緯線（投影図法の場合はx軸）に沿った格子点数（Ni、Nx）
+    pub number_of_points_along_parallel: u32,
+    /// 経線（投影図法の場合はy軸）に沿った格子点数（Nj、Ny）
+    pub number_of_points_along_meridian: u32,
+    /// 最初の格子点の緯度（10^6度単位、南緯は負）
+    pub first_latitude: i32,
+    /// 最初の格子点の経度（10^6度単位、西経は負）
+    pub first_longitude: i32,
+    /// 走査モード（フラグ表3.4）
+    pub scanning_mode: u8,
+    /// 格子系定義テンプレートに従った格子の定義
+    pub grid_definition: GridDefinition,
+}
+
+/// 地球の形状（符号表3.2）
+#[derive(Debug, Clone)]
+pub struct EarthShape {
+    /// 地球の形状を示す符号（符号表3.2）
+    pub shape: u8,
+    /// 長半径（メートル）
+    pub semi_major_axis: f64,
+    /// 短半径（メートル）。球体の場合は長半径と等しい
+    pub semi_minor_axis: f64,
+    /// 測地計算に使用する地球楕円体
+    ellipsoid: Ellipsoid,
+}
+
+impl EarthShape {
+    fn new(shape: u8, ellipsoid: Ellipsoid) -> Self {
+        Self {
+            shape,
+            semi_major_axis: ellipsoid.semi_major_axis,
+            semi_minor_axis: ellipsoid.semi_minor_axis(),
+            ellipsoid,
+        }
+    }
+}
+
+/// 格子系定義テンプレートごとの格子の定義
+#[derive(Debug, Clone)]
+pub enum GridDefinition {
+    /// 緯度・経度格子（格子系定義テンプレート3.0）
+    LatLon(LatLonGrid),
+    /// ポーラーステレオ図法（格子系定義テンプレート3.20）
+    PolarStereographic(ProjectedGrid),
+    /// ランベルト正角円錐図法（格子系定義テンプレート3.30）
+    LambertConformal(ProjectedGrid),
+}
+
+/// 緯度・経度格子（格子系定義テンプレート3.0）
+#[derive(Debug, Clone)]
+pub struct LatLonGrid {
+    /// 最後の格子点の緯度（10^6度単位、南緯は負）
+    pub last_latitude: i32,
+    /// 最後の格子点の経度（10^6度単位、西経は負）
+    pub last_longitude: i32,
     /// i方向（経線方向）の増分（10^6度単位）
     pub longitude_increment: u32,
     /// j方向（緯線方向）の増分（10^6度単位）
     pub latitude_increment: u32,
+    /// i方向（経度）の格子点の並び
+    longitude_axis: LatLonAxis,
+    /// j方向（緯度）の格子点の並び
+    latitude_axis: LatLonAxis,
+}
+
+impl LatLonGrid {
+    /// 緯度・経度格子を作成する。
+    ///
+    /// 格子点の座標を求めるたびに計算しないように、i方向とj方向の格子点の並びを求めておく。
+    ///
+    /// # 引数
+    ///
+    /// * `first` - 最初の格子点の経度と緯度（10^6度単位）。
+    /// * `last` - 最後の格子点の経度と緯度（10^6度単位）。
+    /// * `increments` - i方向とj方向の増分（10^6度単位）。
+    /// * `numbers_of_points` - 緯線と経線に沿った格子点数。
+    /// * `scanning_mode` - 走査モード（フラグ表3.4）。
+    fn new(
+        first: (i32, i32),
+        last: (i32, i32),
+        increments: (u32, u32),
+        numbers_of_points: (u32, u32),
+        scanning_mode: u8,
+    ) -> Self {
+        let ((first_longitude, first_latitude), (last_longitude, last_latitude)) = (first, last);
+        // 最初の格子点から最後の格子点までの、走査方向に沿った距離
+        // 経度は、日付変更線をまたぐ格子でも走査方向に数える
+        let lon_distance = if scanning_mode & SCANNING_MODE_I_NEGATIVE == 0 {
+            last_longitude as i64 - first_longitude as i64
+        } else {
+            first_longitude as i64 - last_longitude as i64
+        };
+        let lat_distance = if scanning_mode & SCANNING_MODE_J_POSITIVE == 0 {
+            first_latitude as i64 - last_latitude as i64
+        } else {
+            last_latitude as i64 - first_latitude as i64
+        };
+
+        Self {
+            last_latitude,
+            last_longitude,
+            longitude_increment: increments.0,
+            latitude_increment: increments.1,
+            longitude_axis: LatLonAxis::new(
+                first_longitude,
+                lon_distance.rem_euclid(FULL_CIRCLE),
+                increments.0,
+                numbers_of_points.0,
+            ),
+            latitude_axis: LatLonAxis::new(
+                first_latitude,
+                lat_distance,
+                increments.1,
+                numbers_of_points.1,
+            ),
+        }
+    }
+
+    /// i方向とj方向の格子点の間隔（10^6度単位）を返却する。
+    fn step(&self) -> (f64, f64) {
+        (self.longitude_axis.step(), self.latitude_axis.step())
+    }
+}
+
+/// ポーラーステレオ図法（格子系定義テンプレート3.20）またはランベルト正角円錐図法
+/// （格子系定義テンプレート3.30）で投影された格子
+#[derive(Debug, Clone)]
+pub struct ProjectedGrid {
+    /// x方向とy方向の格子間隔が正しい緯度（10^6度単位、南緯は負）
+    pub lad: i32,
+    /// 格子の向きを定義する、y軸に平行な経線の経度（10^6度単位、西経は負）
+    pub lov: i32,
+    /// x方向の格子間隔（10^-3メートル単位）
+    pub dx: u32,
+    /// y方向の格子間隔（10^-3メートル単位）
+    pub dy: u32,
+    /// 投影中心フラグ（フラグ表3.5）
+    pub projection_centre: u8,
+    /// 割線が地球と交わる最初の緯度（10^6度単位、南緯は負）。ランベルト正角円錐図法の場合のみ
+    pub latin1: Option<i32>,
+    /// 割線が地球と交わる2番目の緯度（10^6度単位、南緯は負）。ランベルト正角円錐図法の場合のみ
+    pub latin2: Option<i32>,
+    /// 投影面上の格子
+    plane: ProjectedPlane,
+}
+
+impl Section3 {
+    /// 資料の並び順で`index`番目の格子点の経度と緯度を返却する。
+    ///
+    /// 格子点は、走査モード（フラグ表3.4）に従って、最初の格子点から並んでいる。
+    /// 走査モードが0x00の場合、格子点は最初の格子点から緯線（x軸）に沿って東方向に並び、
+    /// 緯線に沿った格子点数に達したら、1つ南（y軸の負の方向）の行の最初の格子点に移動する。
+    /// 投影図法の格子の場合は、投影面上の座標を逆投影して経度と緯度を求める。
+    /// 経度は、日付変更線をまたぐ格子でも連続するように、-180度より大きく180度以下に正規化する。
+    fn coordinate(&self, index: u32) -> (i32, i32) {
+        let (i, j) = self.grid_index(index);
+
+        self.point_coordinate(i, j)
+    }
+
+    /// 資料の並び順で`index`番目から`count`個の格子点が、格子点数の範囲内にあるか確認する。
+    pub(crate) fn check_point_range(&self, index: u32, count: u32) -> anyhow::Result<()> {
+        if (self.number_of_points as u64) < index as u64 + count as u64 {
+            return Err(anyhow!(
+                "the number of values exceeds the number of points (number of points:{})",
+                self.number_of_points
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// 最初の格子点からi方向に`i`番目、j方向に`j`番目の格子点の経度と緯度を返却する。
+    fn point_coordinate(&self, i: u32, j: u32) -> (i32, i32) {
+        let (di, dj) = self.grid_offset(i, j);
+        match &self.grid_definition {
+            GridDefinition::LatLon(grid) => (
+                normalize_longitude(grid.longitude_axis.coordinate(di)),
+                grid.latitude_axis.coordinate(dj) as i32,
+            ),
+            GridDefinition::PolarStereographic(grid) | GridDefinition::LambertConformal(grid) => {
+                let (latitude, longitude) = grid.plane.coordinate(di as f64, dj as f64);
+                (
+                    normalize_longitude((longitude * 1_000_000f64).round() as i64),
+                    (latitude * 1_000_000f64).round() as i32,
+                )
+            }
+        }
+    }
+
+    /// 最初の格子点からi方向に`i`番目、j方向に`j`番目の格子点まで、i方向（東または+x）とj方向（北または+y）に
+    /// 移動する格子の数を返却する。
+    fn grid_offset(&self, i: u32, j: u32) -> (i64, i64) {
+        let di = if self.scanning_mode & SCANNING_MODE_I_NEGATIVE == 0 {
+            i as i64
+        } else {
+            -(i as i64)
+        };
+        let dj = if self.scanning_mode & SCANNING_MODE_J_POSITIVE == 0 {
+            -(j as i64)
+        } else {
+            j as i64
+        };
+
+        (di, dj)
+    }
+
+    /// 資料の並び順で`index`番目の格子点を中心とする格子の、4つの頂点の経度と緯度（度）を返却する。
+    ///
+    /// 頂点は、格子点からi方向とj方向にそれぞれ格子間隔の半分だけ離れた位置で、格子を一周する順番に並べる。
+    /// 頂点の経度は、-180度より大きく180度以下に正規化した格子点の経度との差が、180度以下になるように表現する。
+    pub(crate) fn cell_corners(&self, index: u32) -> [(f64, f64); 4] {
+        const CORNERS: [(f64, f64); 4] = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)];
+        let (i, j) = self.grid_index(index);
+        let (longitude, latitude) = self.point_coordinate(i, j);
+        let (longitude, latitude) = (
+            longitude as f64 / 1_000_000f64,
+            latitude as f64 / 1_000_000f64,
+        );
+        match &self.grid_definition {
+            GridDefinition::LatLon(grid) => {
+                let (lon_step, lat_step) = grid.step();
+                let (lon_step, lat_step) = (lon_step / 1_000_000f64, lat_step / 1_000_000f64);
+                CORNERS.map(|(x, y)| (longitude + x * lon_step, latitude + y * lat_step))
+            }
+            GridDefinition::PolarStereographic(grid) | GridDefinition::LambertConformal(grid) => {
+                let (di, dj) = self.grid_offset(i, j);
+                CORNERS.map(|(x, y)| {
+                    let (corner_latitude, corner_longitude) =
+                        grid.plane.coordinate(di as f64 + x, dj as f64 + y);
+                    let dlon = (corner_longitude - longitude + 540.0).rem_euclid(360.0) - 180.0;
+                    (longitude + dlon, corner_latitude)
+                })
+            }
+        }
+    }
+
+    /// 資料の並び順で`index`番目の格子点を中心とする格子の面積（平方メートル）を返却する。
+    ///
+    /// 緯度・経度格子の場合は、格子点から南北と東西にそれぞれ増分の半分だけ離れた緯線と経線で囲まれた領域の、
+    /// 地球の形状に従った面積を返却する。
+    /// 投影図法の格子の場合は、格子点の緯度における縮尺係数で補正した、格子間隔の積を返却する。
+    pub fn cell_area(&self, index: u32) -> f64 {
+        let (_, latitude) = self.coordinate(index);
+        let latitude = latitude as f64 / 1_000_000f64;
+        match &self.grid_definition {
+            GridDefinition::LatLon(grid) => {
+                let (lon_step, lat_step) = grid.step();
+                let half = lat_step / 2_000_000f64;
+                self.earth_shape.ellipsoid.area(
+                    (latitude - half).max(-90.0),
+                    (latitude + half).min(90.0),
+                    lon_step / 1_000_000f64,
+                )
+            }
+            GridDefinition::PolarStereographic(grid) | GridDefinition::LambertConformal(grid) => {
+                grid.plane.cell_area(latitude)
+            }
+        }
+    }
+
+    /// 経度と緯度に最も近い格子点の、最初の格子点から数えたi方向とj方向の位置を返却する。
+    ///
+    /// 格子の範囲外の場合は`None`を返却する。
+    /// 緯度・経度格子の経度は、日付変更線をまたぐ格子や全球の格子でも連続するように、
+    /// 最初の格子点からi方向に数え、最初の格子点より手前の地点は360度を加えて数える。
+    fn nearest_grid_index(&self, longitude: i32, latitude: i32) -> Option<(u32, u32)> {
+        // i方向（東または+x）とj方向（北または+y）の向きを、走査モードに合わせる
+        let i_sign = if self.scanning_mode & SCANNING_MODE_I_NEGATIVE == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+        let j_sign = if self.scanning_mode & SCANNING_MODE_J_POSITIVE == 0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let (di, dj) = match &self.grid_definition {
+            GridDefinition::LatLon(grid) => {
+                let (lon_step, lat_step) = grid.step();
+                let dlon = i_sign
+                    * normalize_longitude(longitude as i64 - self.first_longitude as i64) as f64;
+                let mut di = dlon / lon_step;
+                if di.round() < 0.0 {
+                    di += FULL_CIRCLE as f64 / lon_step;
+                }
+                let dlat = j_sign * (latitude as i64 - self.first_latitude as i64) as f64;
+                (di, dlat / lat_step)
+            }
+            GridDefinition::PolarStereographic(grid) | GridDefinition::LambertConformal(grid) => {
+                let (x, y) = grid.plane.grid_position(
+                    latitude as f64 / 1_000_000f64,
+                    longitude as f64 / 1_000_000f64,
+                );
+                (i_sign * x, j_sign * y)
+            }
+        };
+        let (mut i, j) = (di.round(), dj.round());
+        let (ni, nj) = (
+            self.number_of_points_along_parallel,
+            self.number_of_points_along_meridian,
+        );
+        // 全球の緯度・経度格子では、最後の格子点より東の点は最初の格子点に近い
+        if let GridDefinition::LatLon(grid) = &self.grid_definition {
+            let (lon_step, _) = grid.step();
+            if i == ni as f64 && FULL_CIRCLE as f64 <= (ni as f64 * lon_step).round() {
+                i = 0.0;
+            }
+        }
+        // NaN（格子間隔が0の場合）を範囲外として扱うため、範囲内であることを確認する
+        if (0.0..ni as f64).contains(&i) && (0.0..nj as f64).contains(&j) {
+            Some((i as u32, j as u32))
+        } else {
+            None
+        }
+    }
+
+    /// 資料の並び順で`index`番目の格子点の、最初の格子点から数えたi方向とj方向の位置を返却する。
+    fn grid_index(&self, index: u32) -> (u32, u32) {
+        // 連続して並ぶ方向の格子点数
+        let length = if self.scanning_mode & SCANNING_MODE_J_CONSECUTIVE == 0 {
+            self.number_of_points_along_parallel
+        } else {
+            self.number_of_points_along_meridian
+        };
+        let line = index / length;
+        let mut position = index % length;
+        // 隣接する行（列）を逆方向に走査する場合、奇数番目の行（列）は逆順に並んでいる
+        if self.scanning_mode & SCANNING_MODE_BOUSTROPHEDON != 0 && line % 2 == 1 {
+            position = length - 1 - position;
+        }
+
+        if self.scanning_mode & SCANNING_MODE_J_CONSECUTIVE == 0 {
+            (position, line)
+        } else {
+            (line, position)
+        }
+    }
+}
+
+/// 緯度・経度格子の、i方向またはj方向の格子点の並び
+///
+/// 最初の格子点から`position`番目の格子点の座標（10^6度単位）は、
+/// `(origin + position * step) / denominator`を四捨五入した値。
+///
+/// 第3節の最初の格子点と増分は10^-6度単位に丸められているため（例えば1/120度は8333）、
+/// 増分を格子点数倍すると、最初の格子点から離れるほど真の格子点からずれる。
+/// そこで、最後の格子点が格子点数と増分に丸め誤差の範囲で整合する場合は、最初と最後の格子点の間を等分する。
+/// さらに、格子点の間隔が1/k度で、最初の格子点が1/2k度の倍数と丸め誤差の範囲で一致する場合は、
+/// 1/2k度単位で格子点の座標を求めて、丸める前の格子点の座標を再現する。
+#[derive(Debug, Clone, Copy)]
+struct LatLonAxis {
+    origin: i64,
+    step: i64,
+    denominator: i64,
+}
+
+impl LatLonAxis {
+    /// 格子点の並びを作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `first` - 最初の格子点の座標（10^6度単位）。
+    /// * `distance` - 最初の格子点から最後の格子点までの、走査方向に沿った距離（10^6度単位）。
+    /// * `increment` - 第3節の増分（10^6度単位）。
+    /// * `number_of_points` - 格子点数。
+    fn new(first: i32, distance: i64, increment: u32, number_of_points: u32) -> Self {
+        let first = first as i64;
+        let steps = (number_of_points as i64 - 1).max(0);
+        if steps == 0 || steps < (distance - steps * increment as i64).abs() {
+            // 格子点数が1の場合、または最後の格子点が整合しない場合は、増分を格子点の間隔とする
+            return Self {
+                origin: first,
+                step: increment as i64,
+                denominator: 1,
+            };
+        }
+        // 格子点の間隔が1/k度の場合
+        let k = if 0 < distance {
+            (1_000_000f64 * steps as f64 / distance as f64).round() as i64
+        } else {
+            0
+        };
+        if 0 < k && (distance * k - steps * 1_000_000).abs() <= k {
+            let halves = (first as f64 * 2.0 * k as f64 / 1_000_000f64).round() as i64;
+            if (halves * 1_000_000 - first * 2 * k).abs() <= k {
+                return Self {
+                    origin: halves * 1_000_000,
+                    step: 2_000_000,
+                    denominator: 2 * k,
+                };
+            }
+        }
+
+        Self {
+            origin: first * steps,
+            step: distance,
+            denominator: steps,
+        }
+    }
+
+    /// 最初の格子点から走査方向に`position`番目（負の場合は逆方向）の格子点の座標（10^6度単位）を返却する。
+    fn coordinate(&self, position: i64) -> i64 {
+        let value = self.origin + position * self.step;
+
+        (2 * value + self.denominator).div_euclid(2 * self.denominator)
+    }
+
+    /// 格子点の間隔（10^6度単位）を返却する。
+    fn step(&self) -> f64 {
+        self.step as f64 / self.denominator as f64
+    }
 }
 
 /// 第3節を読み込んで、第3節の情報を返却する。
 ///
 /// ファイルポインタが、第3節の開始位置にあることを想定している。
 /// 関数終了後、ファイルポインタは第4節の開始位置に移動する。
-fn read_section3(reader: &mut FileReader) -> anyhow::Result<Section3> {
-    // 節の長さ: 4bytes
-    reader.seek_relative(4)?;
+fn read_section3<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Section3> {
+    let start = reader.stream_position()?;
+    // 節の長さ
+    let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of section 3"))?;
     // 節番号
     let section_number =
         read_u8(reader).map_err(|_| anyhow!("failed to read section number at section 3"))?;
@@ -567,54 +1860,212 @@ fn read_section3(reader: &mut FileReader) -> anyhow::Result<Section3> {
     // 格子点を定義するリストの説明: 1byte
     reader.seek_relative(2)?;
     // 格子系定義テンプレート番号
-    read_section3_grid_system_definition_template(reader)?;
+    let template = read_section3_grid_system_definition_template(reader)?;
     // 地球の形状
-    read_section3_earth_figure(reader)?;
-    // 地球球体の半径の尺度因子: 1byte
-    // 地球球体の半径の尺度付き半径: 4bytes
-    // 地球回転楕円体の長軸の尺度因子: 1byte
-    // 地球回転楕円体の長軸の尺度付きの長さ: 4byte
-    // 地球回転楕円体の短軸の尺度因子: 1byte
-    // 地球回転楕円体の短軸の尺度付きの長さ: 4byte
-    reader.seek_relative(15)?;
+    let earth_shape = read_section3_earth_shape(reader)?;
     // 緯線に沿った格子点数
-    read_section3_number_of_points_at_vertical(reader)?;
+    let number_of_points_along_parallel = read_section3_number_of_points_along_parallel(reader)?;
     // 経線に沿った格子点数
-    read_section3_number_of_points_at_horizontal(reader)?;
+    let number_of_points_along_meridian = read_section3_number_of_points_along_meridian(reader)?;
+    if number_of_points_along_parallel as u64 * number_of_points_along_meridian as u64
+        != number_of_points as u64
+    {
+        return Err(anyhow!(
+            "the number of points is different from Ni x Nj (points:{number_of_points}, Ni:{number_of_points_along_parallel}, Nj:{number_of_points_along_meridian})"
+        ));
+    }
+    let (first_latitude, first_longitude, scanning_mode, grid_definition) = match template {
+        GRID_SYSTEM_DEFINITION_TEMPLATE_LAT_LON => read_section3_lat_lon_grid(
+            reader,
+            (
+                number_of_points_along_parallel,
+                number_of_points_along_meridian,
+            ),
+        )?,
+        _ => read_section3_projected_grid(reader, template, earth_shape.ellipsoid)?,
+    };
+    // 次の節の開始位置に移動
+    reader.seek(SeekFrom::Start(start + length as u64))?;
+
+    Ok(Section3 {
+        number_of_points,
+        template,
+        earth_shape,
+        number_of_points_along_parallel,
+        number_of_points_along_meridian,
+        first_latitude,
+        first_longitude,
+        scanning_mode,
+        grid_definition,
+    })
+}
+
+/// 第3節 格子系定義テンプレート3.0（緯度・経度格子）の、格子点数より後を読み込む。
+///
+/// # 引数
+///
+/// * `reader` - リーダー。
+/// * `numbers_of_points` - 緯線と経線に沿った格子点数。
+///
+/// # 戻り値
+///
+/// 最初の格子点の緯度と経度、走査モード及び格子の定義を格納したタプル。
+fn read_section3_lat_lon_grid<R: Read + Seek>(
+    reader: &mut R,
+    numbers_of_points: (u32, u32),
+) -> anyhow::Result<(i32, i32, u8, GridDefinition)> {
     // 原作成領域の基本角
     read_section3_creation_range_angle(reader)?;
     // 端点の経度及び緯度並びに方向増分の定義に使われる基本角の細分: 4bytes
     reader.seek_relative(4)?;
     // 最初の格子点の緯度
-    let northernmost = read_section3_northernmost_degree(reader)?;
+    let first_latitude = read_section3_first_latitude(reader)?;
     // 最初の格子点の経度
-    let westernmost = read_section3_westernmost_degree(reader)?;
+    let first_longitude = read_section3_first_longitude(reader)?;
     // 分解能及び成分フラグ: 1byte
     reader.seek_relative(1)?;
     // 最後の格子点の緯度
-    let southernmost = read_section3_southernmost_degree(reader)?;
+    let last_latitude = read_section3_last_latitude(reader)?;
     // 最後の格子点の経度
-    let easternmost = read_section3_easternmost_degree(reader)?;
+    let last_longitude = read_section3_last_longitude(reader)?;
     // i方向の増分
     let horizontal_increment = read_section3_horizontal_increment(reader)?;
     // j方向の増分
     let vertical_increment = read_section3_vertical_increment(reader)?;
     // 走査モード
-    read_section3_scanning_mode(reader)?;
+    let scanning_mode = read_section3_scanning_mode(reader)?;
+
+    Ok((
+        first_latitude,
+        first_longitude,
+        scanning_mode,
+        GridDefinition::LatLon(LatLonGrid::new(
+            (first_longitude, first_latitude),
+            (last_longitude, last_latitude),
+            (horizontal_increment, vertical_increment),
+            numbers_of_points,
+            scanning_mode,
+        )),
+    ))
+}
+
+/// 第3節 格子系定義テンプレート3.20（ポーラーステレオ図法）または3.30（ランベルト正角円錐図法）の、
+/// 格子点数より後を読み込む。
+///
+/// # 戻り値
+///
+/// 最初の格子点の緯度と経度、走査モード及び格子の定義を格納したタプル。
+fn read_section3_projected_grid<R: Read + Seek>(
+    reader: &mut R,
+    template: u16,
+    ellipsoid: Ellipsoid,
+) -> anyhow::Result<(i32, i32, u8, GridDefinition)> {
+    // 最初の格子点の緯度
+    let first_latitude = read_section3_first_latitude(reader)?;
+    // 最初の格子点の経度
+    let first_longitude = read_section3_first_longitude(reader)?;
+    // 分解能及び成分フラグ: 1byte
+    reader.seek_relative(1)?;
+    // 格子間隔が正しい緯度
+    let lad = read_section3_projection_latitude(reader, "LaD")?;
+    // 格子の向きを定義する経度
+    let lov = read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a LoV"))?;
+    // x方向の格子間隔
+    let dx = read_u32(reader).map_err(|_| anyhow!("failed to read a Dx"))?;
+    // y方向の格子間隔
+    let dy = read_u32(reader).map_err(|_| anyhow!("failed to read a Dy"))?;
+    // 投影中心フラグ
+    let projection_centre = read_section3_projection_centre(reader)?;
+    // 走査モード
+    let scanning_mode = read_section3_scanning_mode(reader)?;
+    // 割線が地球と交わる緯度
+    let (latin1, latin2) = if template == GRID_SYSTEM_DEFINITION_TEMPLATE_LAMBERT_CONFORMAL {
+        let latin1 = read_section3_projection_latitude(reader, "Latin 1")?;
+        let latin2 = read_section3_projection_latitude(reader, "Latin 2")?;
+        // 南極の緯度: 4bytes
+        // 南極の経度: 4bytes
+        (Some(latin1), Some(latin2))
+    } else {
+        (None, None)
+    };
+
+    // 投影面上の格子を作成
+    let degree = |value: i32| value as f64 / 1_000_000f64;
+    let projection = match (latin1, latin2) {
+        (Some(latin1), Some(latin2)) => ConicProjection::lambert_conformal(
+            ellipsoid,
+            degree(latin1),
+            degree(latin2),
+            degree(lov),
+        ),
+        _ => ConicProjection::polar_stereographic(
+            ellipsoid,
+            degree(lad),
+            degree(lov),
+            projection_centre & PROJECTION_CENTRE_SOUTH_POLE != 0,
+        ),
+    };
+    let plane = ProjectedPlane::new(
+        projection,
+        degree(first_latitude),
+        degree(first_longitude),
+        dx as f64 / 1_000f64,
+        dy as f64 / 1_000f64,
+        degree(lad),
+    );
+    let grid = ProjectedGrid {
+        lad,
+        lov,
+        dx,
+        dy,
+        projection_centre,
+        latin1,
+        latin2,
+        plane,
+    };
+    let grid_definition = if template == GRID_SYSTEM_DEFINITION_TEMPLATE_LAMBERT_CONFORMAL {
+        GridDefinition::LambertConformal(grid)
+    } else {
+        GridDefinition::PolarStereographic(grid)
+    };
+
+    Ok((
+        first_latitude,
+        first_longitude,
+        scanning_mode,
+        grid_definition,
+    ))
+}
+
+/// 第3節 投影図法の定義に使用する緯度を読み込んで、緯度の範囲内であるか確認する。
+fn read_section3_projection_latitude<R: Read>(reader: &mut R, name: &str) -> anyhow::Result<i32> {
+    let value = read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a {name}"))?;
+    match value {
+        -90_000_000..=90_000_000 => Ok(value),
+        _ => Err(anyhow!("a {name} is out of range (value:{value})")),
+    }
+}
 
-    Ok(Section3 {
-        number_of_points,
-        northernmost,
-        westernmost,
-        southernmost,
-        easternmost,
-        longitude_increment: horizontal_increment,
-        latitude_increment: vertical_increment,
-    })
+/// 第3節 投影中心フラグを読み込んで、対応している投影中心であるか確認する。
+///
+/// 投影中心が1つの場合のみに対応している。
+fn read_section3_projection_centre<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
+    let value = read_u8(reader).map_err(|_| anyhow!("failed to read a projection centre flag"))?;
+    if value & PROJECTION_CENTRE_BIPOLAR != 0 {
+        return Err(anyhow!(
+            "a projection centre flag {value:#04x} is not supported"
+        ));
+    }
+
+    Ok(value)
 }
 
 /// 第3節 格子系定義の出典を読み込んで、想定している格子系定義の出典であるか確認する。
-fn read_section3_grid_system_definition(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section3_grid_system_definition<R: Read>(reader: &mut R) -> anyhow::Result<()> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a grid system definition"))?;
     match value {
         GRID_SYSTEM_DEFINITION => Ok(()),
@@ -625,57 +2076,109 @@ fn read_section3_grid_system_definition(reader: &mut FileReader) -> anyhow::Resu
 }
 
 /// 第3節 資料点数を読み込んで、返却する。
-fn read_section3_number_of_points(reader: &mut FileReader) -> anyhow::Result<u32> {
+fn read_section3_number_of_points<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
     read_u32(reader).map_err(|_| anyhow!("failed to read a number of points in section 3"))
 }
 
-/// 第3節 格子系定義テンプレート番号を読み込んで、想定している格子系定義テンプレート番号であるか確認する。
-fn read_section3_grid_system_definition_template(reader: &mut FileReader) -> anyhow::Result<()> {
+/// 第3節 格子系定義テンプレート番号を読み込んで、対応している格子系定義テンプレート番号であるか確認する。
+fn read_section3_grid_system_definition_template<R: Read>(reader: &mut R) -> anyhow::Result<u16> {
     let value = read_u16(reader)
         .map_err(|_| anyhow!("failed to read a grid system definition template"))?;
     match value {
-        GRID_SYSTEM_DEFINITION_TEMPLATE => Ok(()),
+        GRID_SYSTEM_DEFINITION_TEMPLATE_LAT_LON
+        | GRID_SYSTEM_DEFINITION_TEMPLATE_POLAR_STEREOGRAPHIC
+        | GRID_SYSTEM_DEFINITION_TEMPLATE_LAMBERT_CONFORMAL => Ok(value),
         _ => Err(anyhow!(
-            "a grid system definition template is not {GRID_SYSTEM_DEFINITION_TEMPLATE}"
+            "a grid system definition template {value} is not supported"
         )),
     }
 }
 
-/// 第3節 地球の形状を読み込んで、想定している地球の形状であるか確認する。
-fn read_section3_earth_figure(reader: &mut FileReader) -> anyhow::Result<()> {
-    let value = read_u8(reader).map_err(|_| anyhow!("failed to read a earth figure"))?;
-    match value {
-        EARTH_FIGURE => Ok(()),
-        _ => Err(anyhow!("a earth figure is not {EARTH_FIGURE}")),
+/// 第3節 地球の形状と、地球球体の半径及び地球回転楕円体の軸の長さを読み込んで、地球の形状を返却する。
+fn read_section3_earth_shape<R: Read>(reader: &mut R) -> anyhow::Result<EarthShape> {
+    let shape = read_u8(reader).map_err(|_| anyhow!("failed to read a shape of the earth"))?;
+    // 地球球体の半径
+    let radius = read_section3_scaled_value(reader, "radius of the earth")?;
+    // 地球回転楕円体の長軸の長さ
+    let major_axis = read_section3_scaled_value(reader, "major axis of the earth")?;
+    // 地球回転楕円体の短軸の長さ
+    let minor_axis = read_section3_scaled_value(reader, "minor axis of the earth")?;
+
+    let specified = |value: Option<f64>, name: &str| {
+        value
+            .filter(|value| 0.0 < *value)
+            .ok_or_else(|| anyhow!("a {name} is not specified for a shape of the earth {shape}"))
+    };
+    let ellipsoid = match shape {
+        EARTH_SHAPE_SPHERE_6367470 => Ellipsoid::from_axes(6_367_470.0, 6_367_470.0),
+        EARTH_SHAPE_SPHERE_SPECIFIED => {
+            let radius = specified(radius, "radius of the earth")?;
+            Ellipsoid::from_axes(radius, radius)
+        }
+        EARTH_SHAPE_IAU1965 => Ellipsoid::from_flattening(6_378_160.0, 297.0),
+        EARTH_SHAPE_SPHEROID_SPECIFIED_KM | EARTH_SHAPE_SPHEROID_SPECIFIED_M => {
+            let unit = if shape == EARTH_SHAPE_SPHEROID_SPECIFIED_KM {
+                1_000.0
+            } else {
+                1.0
+            };
+            let major_axis = specified(major_axis, "major axis of the earth")?;
+            let minor_axis = specified(minor_axis, "minor axis of the earth")?;
+            if major_axis < minor_axis {
+                return Err(anyhow!(
+                    "a major axis of the earth is shorter than a minor axis (major:{major_axis}, minor:{minor_axis})"
+                ));
+            }
+            Ellipsoid::from_axes(major_axis * unit, minor_axis * unit)
+        }
+        EARTH_SHAPE_GRS80 => Ellipsoid::grs80(),
+        EARTH_SHAPE_WGS84 => Ellipsoid::from_flattening(6_378_137.0, 298.257_223_563),
+        EARTH_SHAPE_SPHERE_6371229 => Ellipsoid::from_axes(6_371_229.0, 6_371_229.0),
+        EARTH_SHAPE_SPHERE_6371200 => Ellipsoid::from_axes(6_371_200.0, 6_371_200.0),
+        EARTH_SHAPE_OSGB1936 => Ellipsoid::from_axes(6_377_563.396, 6_356_256.909),
+        _ => return Err(anyhow!("a shape of the earth {shape} is not supported")),
+    };
+
+    Ok(EarthShape::new(shape, ellipsoid))
+}
+
+/// 第3節 尺度因子と尺度付きの値を読み込んで、値を返却する。
+///
+/// 尺度因子または尺度付きの値が欠損値（すべてのビットが1）の場合は`None`を返却する。
+fn read_section3_scaled_value<R: Read>(reader: &mut R, name: &str) -> anyhow::Result<Option<f64>> {
+    let factor = read_u8(reader).map_err(|_| anyhow!("failed to read a scale factor of {name}"))?;
+    let value = read_u32(reader).map_err(|_| anyhow!("failed to read a scaled value of {name}"))?;
+    if factor == u8::MAX || value == u32::MAX {
+        return Ok(None);
     }
+
+    Ok(Some(
+        value as f64 / 10f64.powi(sign_magnitude_i8(factor) as i32),
+    ))
 }
 
-/// 第3節 緯線に沿った格子点数を読み込んで、想定している点数であるか確認する。
-fn read_section3_number_of_points_at_vertical(reader: &mut FileReader) -> anyhow::Result<()> {
-    let value =
-        read_u32(reader).map_err(|_| anyhow!("failed to read a number of points at vertical"))?;
+/// 第3節 緯線に沿った格子点数を読み込んで、1以上であるか確認する。
+fn read_section3_number_of_points_along_parallel<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
+    let value = read_u32(reader)
+        .map_err(|_| anyhow!("failed to read a number of points along a parallel"))?;
     match value {
-        NUMBER_OF_POINT_AT_VERTICAL => Ok(()),
-        _ => Err(anyhow!(
-            "a number of points at vertical is not {NUMBER_OF_POINT_AT_VERTICAL}"
-        )),
+        0 => Err(anyhow!("a number of points along a parallel is 0")),
+        _ => Ok(value),
     }
 }
 
-/// 第3節 経線に沿った格子点数を読み込んで、想定している点数であるか確認する。
-fn read_section3_number_of_points_at_horizontal(reader: &mut FileReader) -> anyhow::Result<()> {
-    let value =
-        read_u32(reader).map_err(|_| anyhow!("failed to read a number of points at horizontal"))?;
+/// 第3節 経線に沿った格子点数を読み込んで、1以上であるか確認する。
+fn read_section3_number_of_points_along_meridian<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
+    let value = read_u32(reader)
+        .map_err(|_| anyhow!("failed to read a number of points along a meridian"))?;
     match value {
-        NUMBER_OF_POINT_AT_HORIZONTAL => Ok(()),
-        _ => Err(anyhow!(
-            "a number of points at horizontal is not {NUMBER_OF_POINT_AT_HORIZONTAL}"
-        )),
+        0 => Err(anyhow!("a number of points along a meridian is 0")),
+        _ => Ok(value),
     }
 }
 
 /// 第3節 原作成領域の基本角を読み込んで、想定している角度であるか確認する。
-fn read_section3_creation_range_angle(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section3_creation_range_angle<R: Read>(reader: &mut R) -> anyhow::Result<()> {
     let value = read_u32(reader).map_err(|_| anyhow!("failed to read a creation range angle"))?;
     match value {
         CREATION_RANGE_ANGLE => Ok(()),
@@ -686,49 +2189,240 @@ fn read_section3_creation_range_angle(reader: &mut FileReader) -> anyhow::Result
 }
 
 /// 第3節 最初の格子点の緯度を読み込んで、返却する。
-fn read_section3_northernmost_degree(reader: &mut FileReader) -> anyhow::Result<u32> {
-    read_u32(reader).map_err(|_| anyhow!("failed to read a northernmost grid point latitude"))
+fn read_section3_first_latitude<R: Read>(reader: &mut R) -> anyhow::Result<i32> {
+    read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a first grid point latitude"))
 }
 
 /// 第3節 最初の格子点の経度を読み込んで、返却する。
-fn read_section3_westernmost_degree(reader: &mut FileReader) -> anyhow::Result<u32> {
-    read_u32(reader).map_err(|_| anyhow!("failed to read a westernmost grid point longitude"))
+fn read_section3_first_longitude<R: Read>(reader: &mut R) -> anyhow::Result<i32> {
+    read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a first grid point longitude"))
 }
 
 /// 第3節 最後の格子点の緯度を読み込んで、返却する。
-fn read_section3_southernmost_degree(reader: &mut FileReader) -> anyhow::Result<u32> {
-    read_u32(reader).map_err(|_| anyhow!("failed to read a southernmost grid point latitude"))
+fn read_section3_last_latitude<R: Read>(reader: &mut R) -> anyhow::Result<i32> {
+    read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a last grid point latitude"))
 }
 
 /// 第3節 最後の格子点の経度を読み込んで、返却する。
-fn read_section3_easternmost_degree(reader: &mut FileReader) -> anyhow::Result<u32> {
-    read_u32(reader).map_err(|_| anyhow!("failed to read a easternmost grid point longitude"))
+fn read_section3_last_longitude<R: Read>(reader: &mut R) -> anyhow::Result<i32> {
+    read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a last grid point longitude"))
 }
 
 /// 第3節 i方向（経線方向）の増分を読み込んで、想定している増分か確認する。
-fn read_section3_horizontal_increment(reader: &mut FileReader) -> anyhow::Result<u32> {
+fn read_section3_horizontal_increment<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
     read_u32(reader).map_err(|_| anyhow!("failed to read a horizontal increment"))
 }
 
 /// 第3節 j方向（緯線方向）の増分を読み込んで、想定している増分か確認する。
-fn read_section3_vertical_increment(reader: &mut FileReader) -> anyhow::Result<u32> {
+fn read_section3_vertical_increment<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
     read_u32(reader).map_err(|_| anyhow!("failed to read a vertical increment"))
 }
 
-/// 第3節 走査モードを読み込んで、想定しているモードか確認する。
-fn read_section3_scanning_mode(reader: &mut FileReader) -> anyhow::Result<()> {
+/// 第3節 走査モードを読み込んで、対応しているモードか確認する。
+///
+/// フラグ表3.4のビット1からビット4に対応しており、ビット5からビット8が設定されている場合はエラーとする。
+fn read_section3_scanning_mode<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a scanning mode"))?;
-    match value {
-        SCANNING_MODE => Ok(()),
-        _ => Err(anyhow!("a scanning mode is not {SCANNING_MODE}")),
+    match value & !SUPPORTED_SCANNING_MODE {
+        0 => Ok(value),
+        _ => Err(anyhow!("a scanning mode {value:#04x} is not supported")),
+    }
+}
+
+/// 第4節 プロダクト定義テンプレート番号: 解析又は予報（ある時点）
+const PRODUCT_DEFINITION_TEMPLATE_INSTANT: u16 = 0;
+/// 第4節 プロダクト定義テンプレート番号: 時間間隔での統計処理
+const PRODUCT_DEFINITION_TEMPLATE_STATISTICAL: u16 = 8;
+/// 第4節 プロダクト定義テンプレート番号: 時間間隔での統計処理（気象庁定義、解析雨量）
+const PRODUCT_DEFINITION_TEMPLATE_JMA_ANALYSIS: u16 = 50008;
+/// 第4節 プロダクト定義テンプレート番号: 時間間隔での統計処理（気象庁定義、降水短時間予報）
+const PRODUCT_DEFINITION_TEMPLATE_JMA_FORECAST: u16 = 50009;
+
+/// 第4節情報
+#[derive(Debug, Clone)]
+pub struct Section4 {
+    /// テンプレート直後の座標値の数
+    pub number_of_coordinates: u16,
+    /// プロダクト定義テンプレート番号
+    pub template: u16,
+    /// プロダクト定義
+    ///
+    /// テンプレート4.0、4.8、4.50008及び4.50009以外のテンプレートの場合は`None`。
+    pub product_definition: Option<ProductDefinition>,
+}
+
+/// プロダクト定義
+///
+/// テンプレート4.0、4.8、4.50008及び4.50009に共通する項目と、テンプレートごとの項目を格納する。
+#[derive(Debug, Clone)]
+pub struct ProductDefinition {
+    /// パラメータ・カテゴリー（符号表4.1）
+    pub parameter_category: u8,
+    /// パラメータ番号（符号表4.2）
+    pub parameter_number: u8,
+    /// 作成処理の種類（符号表4.3）
+    pub generating_process: u8,
+    /// 背景作成処理識別符
+    pub background_process: u8,
+    /// 予報の作成処理識別符
+    pub forecast_process: u8,
+    /// 観測資料の参照時刻からの締切時間（時）
+    pub cutoff_hours: u16,
+    /// 観測資料の参照時刻からの締切時間（分）
+    pub cutoff_minutes: u8,
+    /// 期間の単位の指示符（符号表4.4）
+    pub time_unit: u8,
+    /// 予報時間（期間の単位の指示符で示した単位）
+    ///
+    /// 統計処理をしたプロダクトの場合は、統計処理を開始する時刻を示す。
+    pub forecast_time: i32,
+    /// 第一固定面
+    pub first_surface: FixedSurface,
+    /// 第二固定面
+    pub second_surface: FixedSurface,
+    /// 時間間隔での統計処理
+    ///
+    /// テンプレート4.8、4.50008及び4.50009の場合のみ`Some`。
+    pub statistical_process: Option<StatisticalProcess>,
+    /// 気象庁定義の運用情報
+    ///
+    /// テンプレート4.50008及び4.50009の場合のみ`Some`。
+    pub operation_info: Option<OperationInfo>,
+}
+
+impl ProductDefinition {
+    /// 予報時間を期間で返却する。
+    ///
+    /// 期間の単位の指示符が、分、時、日、3時間、6時間、12時間及び秒以外の場合は`None`を返却する。
+    pub fn forecast_duration(&self) -> Option<Duration> {
+        time_unit_duration(self.time_unit, self.forecast_time as i64)
+    }
+
+    /// 時間間隔での統計処理をしたプロダクトか確認する。
+    ///
+    /// `true`の場合は積算値などの統計値、`false`の場合はある時点の値（強度など）である。
+    pub fn is_statistical(&self) -> bool {
+        self.statistical_process.is_some()
+    }
+}
+
+/// 固定面
+#[derive(Debug, Clone, Copy)]
+pub struct FixedSurface {
+    /// 固定面の種類（符号表4.5）
+    pub surface_type: u8,
+    /// 固定面の尺度因子（欠損値の場合は`None`）
+    pub scale_factor: Option<i8>,
+    /// 固定面の尺度付きの値（欠損値の場合は`None`）
+    pub scaled_value: Option<i32>,
+}
+
+/// 時間間隔での統計処理
+#[derive(Debug, Clone)]
+pub struct StatisticalProcess {
+    /// 全時間間隔の終了時
+    pub end_of_overall_time_interval: PrimitiveDateTime,
+    /// 統計処理で欠損した資料の総数
+    pub number_of_missing_values: u32,
+    /// 統計処理の時間範囲の仕様
+    pub time_ranges: Vec<TimeRange>,
+}
+
+/// 統計処理の時間範囲の仕様
+#[derive(Debug, Clone, Copy)]
+pub struct TimeRange {
+    /// 統計処理の種類（符号表4.10、0:平均、1:積算、2:最大、3:最小）
+    pub statistical_process: u8,
+    /// 統計処理の時間増分の種類（符号表4.11）
+    pub time_increment_type: u8,
+    /// 統計処理の時間の単位の指示符（符号表4.4）
+    pub time_range_unit: u8,
+    /// 統計処理した期間の長さ
+    pub time_range_length: u32,
+    /// 連続的な資料場間の増分に関する時間の単位の指示符（符号表4.4）
+    pub time_increment_unit: u8,
+    /// 連続的な資料場間の時間の増分
+    pub time_increment: u32,
+}
+
+impl TimeRange {
+    /// 統計処理した期間の長さを期間で返却する。
+    pub fn duration(&self) -> Option<Duration> {
+        time_unit_duration(self.time_range_unit, self.time_range_length as i64)
+    }
+}
+
+/// 気象庁定義の運用情報
+///
+/// テンプレート4.50008及び4.50009は、テンプレート4.8の項目の後に、これらの運用情報を記録している。
+#[derive(Debug, Clone, Copy)]
+pub struct OperationInfo {
+    /// レーダー等運用情報その1
+    pub radar1: u64,
+    /// レーダー等運用情報その2
+    pub radar2: u64,
+    /// 雨量計運用情報
+    pub rain_gauge: u64,
+}
+
+/// 期間の単位の指示符（符号表4.4）と値から、期間を返却する。
+fn time_unit_duration(unit: u8, value: i64) -> Option<Duration> {
+    match unit {
+        0 => Some(Duration::minutes(value)),
+        1 => Some(Duration::hours(value)),
+        2 => Some(Duration::days(value)),
+        10 => Some(Duration::hours(value * 3)),
+        11 => Some(Duration::hours(value * 6)),
+        12 => Some(Duration::hours(value * 12)),
+        13 => Some(Duration::seconds(value)),
+        _ => None,
+    }
+}
+
+/// 最上位ビットを符号、残りのビットを絶対値として記録された32ビット整数を、i32型の値に変換する。
+fn sign_magnitude_i32(value: u32) -> i32 {
+    let magnitude = (value & 0x7fff_ffff) as i32;
+    if value & 0x8000_0000 == 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// 最上位ビットを符号、残りのビットを絶対値として記録された16ビット整数を、i16型の値に変換する。
+fn sign_magnitude_i16(value: u16) -> i16 {
+    let magnitude = (value & 0x7fff) as i16;
+    if value & 0x8000 == 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// 最上位ビットを符号、残りのビットを絶対値として記録された8ビット整数を、i8型の値に変換する。
+fn sign_magnitude_i8(value: u8) -> i8 {
+    let magnitude = (value & 0x7f) as i8;
+    if value & 0x80 == 0 {
+        magnitude
+    } else {
+        -magnitude
     }
 }
 
-/// 第4節を読み込んで確認する。
+/// 第4節を読み込んで、第4節の情報を返却する。
 ///
 /// ファイルポインタが、第4節の開始位置にあることを想定している。
 /// 関数終了後、ファイルポインタは第5節の開始位置に移動する。
-fn read_section4(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section4<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Section4> {
+    let start = reader.stream_position()?;
     // 第4節 節の長さを読み込み
     let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of section 4"))?;
     // 節番号
@@ -737,34 +2431,357 @@ fn read_section4(reader: &mut FileReader) -> anyhow::Result<()> {
     if section_number != 4 {
         return Err(anyhow!("section number is miss match in section 4"));
     }
+    // テンプレート直後の座標値の数
+    let number_of_coordinates = read_u16(reader)
+        .map_err(|_| anyhow!("failed to read a number of coordinates in section 4"))?;
+    // プロダクト定義テンプレート番号
+    let template =
+        read_u16(reader).map_err(|_| anyhow!("failed to read a product definition template"))?;
+    // プロダクト定義
+    let product_definition = match template {
+        PRODUCT_DEFINITION_TEMPLATE_INSTANT => {
+            Some(read_section4_product_definition(reader, false, false)?)
+        }
+        PRODUCT_DEFINITION_TEMPLATE_STATISTICAL => {
+            Some(read_section4_product_definition(reader, true, false)?)
+        }
+        PRODUCT_DEFINITION_TEMPLATE_JMA_ANALYSIS | PRODUCT_DEFINITION_TEMPLATE_JMA_FORECAST => {
+            Some(read_section4_product_definition(reader, true, true)?)
+        }
+        _ => None,
+    };
 
-    // テンプレート直後の座標値の数以降をスキップ
-    reader
-        .seek_relative((length - (4 + 1)) as i64)
-        .map_err(|e| e.into())
+    // テンプレート直後の座標値などをスキップ
+    reader.seek(SeekFrom::Start(start + length as u64))?;
+
+    Ok(Section4 {
+        number_of_coordinates,
+        template,
+        product_definition,
+    })
+}
+
+/// 第4節 プロダクト定義テンプレートを読み込んで、返却する。
+///
+/// ファイルポインタが、第4節10オクテット目にあることを想定している。
+///
+/// # 引数
+///
+/// * `reader` - ファイル・リーダー。
+/// * `statistical` - 時間間隔での統計処理を読み込むかを示すフラグ。
+/// * `operation_info` - 気象庁定義の運用情報を読み込むかを示すフラグ。
+fn read_section4_product_definition<R: Read>(
+    reader: &mut R,
+    statistical: bool,
+    operation_info: bool,
+) -> anyhow::Result<ProductDefinition> {
+    // パラメータ・カテゴリー
+    let parameter_category =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a parameter category"))?;
+    // パラメータ番号
+    let parameter_number =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a parameter number"))?;
+    // 作成処理の種類
+    let generating_process =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a generating process"))?;
+    // 背景作成処理識別符
+    let background_process =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a background process"))?;
+    // 予報の作成処理識別符
+    let forecast_process =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a forecast process"))?;
+    // 観測資料の参照時刻からの締切時間（時）
+    let cutoff_hours = read_u16(reader).map_err(|_| anyhow!("failed to read cutoff hours"))?;
+    // 観測資料の参照時刻からの締切時間（分）
+    let cutoff_minutes = read_u8(reader).map_err(|_| anyhow!("failed to read cutoff minutes"))?;
+    // 期間の単位の指示符
+    let time_unit = read_u8(reader).map_err(|_| anyhow!("failed to read a time unit"))?;
+    // 予報時間
+    let forecast_time = read_u32(reader)
+        .map(sign_magnitude_i32)
+        .map_err(|_| anyhow!("failed to read a forecast time"))?;
+    // 第一固定面
+    let first_surface = read_section4_fixed_surface(reader)?;
+    // 第二固定面
+    let second_surface = read_section4_fixed_surface(reader)?;
+    // 時間間隔での統計処理
+    let statistical_process = match statistical {
+        true => Some(read_section4_statistical_process(reader)?),
+        false => None,
+    };
+    // 気象庁定義の運用情報
+    let operation_info = match operation_info {
+        true => Some(read_section4_operation_info(reader)?),
+        false => None,
+    };
+
+    Ok(ProductDefinition {
+        parameter_category,
+        parameter_number,
+        generating_process,
+        background_process,
+        forecast_process,
+        cutoff_hours,
+        cutoff_minutes,
+        time_unit,
+        forecast_time,
+        first_surface,
+        second_surface,
+        statistical_process,
+        operation_info,
+    })
+}
+
+/// 第4節 固定面を読み込んで、返却する。
+fn read_section4_fixed_surface<R: Read>(reader: &mut R) -> anyhow::Result<FixedSurface> {
+    // 固定面の種類
+    let surface_type = read_u8(reader).map_err(|_| anyhow!("failed to read a surface type"))?;
+    // 固定面の尺度因子
+    let scale_factor = read_u8(reader)
+        .map(|v| (v != u8::MAX).then(|| sign_magnitude_i8(v)))
+        .map_err(|_| anyhow!("failed to read a scale factor of a fixed surface"))?;
+    // 固定面の尺度付きの値
+    let scaled_value = read_u32(reader)
+        .map(|v| (v != u32::MAX).then(|| sign_magnitude_i32(v)))
+        .map_err(|_| anyhow!("failed to read a scaled value of a fixed surface"))?;
+
+    Ok(FixedSurface {
+        surface_type,
+        scale_factor,
+        scaled_value,
+    })
+}
+
+/// 第4節 時間間隔での統計処理を読み込んで、返却する。
+fn read_section4_statistical_process<R: Read>(
+    reader: &mut R,
+) -> anyhow::Result<StatisticalProcess> {
+    // 全時間間隔の終了時
+    let end_of_overall_time_interval = read_date_time(reader)
+        .map_err(|e| anyhow!("failed to read an end of overall time interval: {e}"))?;
+    // 統計処理の時間範囲の仕様の数
+    let number_of_time_ranges =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a number of time ranges"))?;
+    // 統計処理で欠損した資料の総数
+    let number_of_missing_values =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a number of missing values"))?;
+    // 統計処理の時間範囲の仕様
+    let mut time_ranges = Vec::new();
+    for _ in 0..number_of_time_ranges {
+        time_ranges.push(read_section4_time_range(reader)?);
+    }
+
+    Ok(StatisticalProcess {
+        end_of_overall_time_interval,
+        number_of_missing_values,
+        time_ranges,
+    })
+}
+
+/// 第4節 統計処理の時間範囲の仕様を読み込んで、返却する。
+fn read_section4_time_range<R: Read>(reader: &mut R) -> anyhow::Result<TimeRange> {
+    // 統計処理の種類
+    let statistical_process =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a statistical process"))?;
+    // 統計処理の時間増分の種類
+    let time_increment_type =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a time increment type"))?;
+    // 統計処理の時間の単位の指示符
+    let time_range_unit =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a time range unit"))?;
+    // 統計処理した期間の長さ
+    let time_range_length =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a time range length"))?;
+    // 連続的な資料場間の増分に関する時間の単位の指示符
+    let time_increment_unit =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a time increment unit"))?;
+    // 連続的な資料場間の時間の増分
+    let time_increment =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a time increment"))?;
+
+    Ok(TimeRange {
+        statistical_process,
+        time_increment_type,
+        time_range_unit,
+        time_range_length,
+        time_increment_unit,
+        time_increment,
+    })
+}
+
+/// 第4節 気象庁定義の運用情報を読み込んで、返却する。
+fn read_section4_operation_info<R: Read>(reader: &mut R) -> anyhow::Result<OperationInfo> {
+    // レーダー等運用情報その1
+    let radar1 =
+        read_u64(reader).map_err(|_| anyhow!("failed to read a radar operation info 1"))?;
+    // レーダー等運用情報その2
+    let radar2 =
+        read_u64(reader).map_err(|_| anyhow!("failed to read a radar operation info 2"))?;
+    // 雨量計運用情報
+    let rain_gauge =
+        read_u64(reader).map_err(|_| anyhow!("failed to read a rain gauge operation info"))?;
+
+    Ok(OperationInfo {
+        radar1,
+        radar2,
+        rain_gauge,
+    })
 }
 
 /// 第5節情報
-struct Section5 {
+#[derive(Debug, Clone)]
+pub struct Section5 {
     /// 全資料点の数
     pub number_of_points: u32,
+    /// 資料表現テンプレート番号
+    pub template: u16,
+    /// 資料表現テンプレートに従った資料の表現方法
+    pub data_representation: DataRepresentation,
+}
+
+/// 資料表現テンプレートごとの資料の表現方法
+#[derive(Debug, Clone)]
+pub enum DataRepresentation {
+    /// 格子点資料－単純圧縮（資料表現テンプレート5.0）
+    SimplePacking(SimplePacking),
+    /// 格子点資料－複合圧縮（資料表現テンプレート5.2）及び複合圧縮と空間差分（資料表現テンプレート5.3）
+    ComplexPacking(ComplexPacking),
+    /// ランレングス圧縮（資料表現テンプレート5.200）
+    RunLength(RunLength),
+}
+
+/// 格子点資料－単純圧縮（資料表現テンプレート5.0）
+#[derive(Debug, Clone)]
+pub struct SimplePacking {
+    /// 参照値（R）
+    pub reference_value: f32,
+    /// 二進尺度因子（E）
+    pub binary_scale_factor: i16,
+    /// 十進尺度因子（D）
+    pub decimal_scale_factor: i16,
+    /// 1データのビット数
+    pub bits_per_value: u8,
+    /// 元の資料値の種類（符号表5.1）
+    pub original_field_type: u8,
+}
+
+impl SimplePacking {
+    /// 圧縮された値に対応する物理値を返却する。
+    ///
+    /// 物理値Yは、圧縮された値をXとしたとき、Y = (R + X * 2^E) / 10^D で求める。
+    pub fn value(&self, packed: i64) -> f64 {
+        let reference_value = self.reference_value as f64;
+        let binary_scale = 2f64.powi(self.binary_scale_factor as i32);
+        let decimal_scale = 10f64.powi(self.decimal_scale_factor as i32);
+
+        (reference_value + packed as f64 * binary_scale) / decimal_scale
+    }
+
+    /// 物理値を文字列で表現するときの小数点以下の桁数を返却する。
+    ///
+    /// 十進尺度因子の桁数に、二進尺度因子による精度を表現するために必要な桁数を加える。
+    pub fn decimal_places(&self) -> usize {
+        let decimal = self.decimal_scale_factor.max(0) as usize;
+        let binary = (-(self.binary_scale_factor.min(0) as f64) * 2f64.log10()).ceil() as usize;
+
+        decimal + binary
+    }
+}
+
+/// 格子点資料－複合圧縮（資料表現テンプレート5.2）及び複合圧縮と空間差分（資料表現テンプレート5.3）
+#[derive(Debug, Clone)]
+pub struct ComplexPacking {
+    /// 参照値、尺度因子、グループの参照値のビット数及び元の資料値の種類
+    pub simple_packing: SimplePacking,
+    /// グループ分割の方法（符号表5.4）
+    pub group_splitting_method: u8,
+    /// 欠測値の管理（符号表5.5）
+    pub missing_value_management: u8,
+    /// 第一欠測値の代替値
+    pub primary_missing_value_substitute: u32,
+    /// 第二欠測値の代替値
+    pub secondary_missing_value_substitute: u32,
+    /// グループの数
+    pub number_of_groups: u32,
+    /// グループの幅の参照値
+    pub group_width_reference: u8,
+    /// グループの幅のビット数
+    pub group_width_bits: u8,
+    /// グループの長さの参照値
+    pub group_length_reference: u32,
+    /// グループの長さの増分
+    pub group_length_increment: u8,
+    /// 最後のグループの真の長さ
+    pub last_group_length: u32,
+    /// 尺度付きグループの長さのビット数
+    pub group_length_bits: u8,
+    /// 空間差分（資料表現テンプレート5.3の場合）
+    pub spatial_differencing: Option<SpatialDifferencing>,
+}
+
+/// 空間差分
+#[derive(Debug, Clone)]
+pub struct SpatialDifferencing {
+    /// 空間差分の次数（1または2）
+    pub order: u8,
+    /// 追加記述子のオクテット数
+    pub number_of_octets: u8,
+}
+
+/// ランレングス圧縮（資料表現テンプレート5.200）
+#[derive(Debug, Clone)]
+pub struct RunLength {
     /// 1データのビット数
     pub bits_per_data: u8,
     /// 今回の圧縮に用いたレベルの最大値
     pub max_level_at_file: u16,
     /// レベルの最大値
-    #[allow(dead_code)]
     pub max_level: u16,
+    /// データ代表値の尺度因子
+    pub data_value_factor: i8,
     /// レベルmに対応するデータ代表値
-    /// レベル値と物理値(mm/h)の対応を格納するコレクション
+    /// レベル値と尺度因子を適用する前の物理値の対応を格納するコレクション
     pub level_values: Vec<u16>,
 }
 
-/// 第5節を読み込んで、第3節の情報を返却する。
+impl RunLength {
+    /// レベル値に対応する物理値を返却する。
+    ///
+    /// 物理値は、データ代表値を10のデータ代表値の尺度因子乗で除した値である。
+    /// レベル0は欠測値であるため、`None`を返却する。
+    /// また、レベル値に対応するデータ代表値が存在しない場合も`None`を返却する。
+    pub fn representative_value(&self, level: u16) -> Option<f64> {
+        if level == 0 {
+            return None;
+        }
+        self.level_values
+            .get((level - 1) as usize)
+            .map(|&value| value as f64 / 10f64.powi(self.data_value_factor as i32))
+    }
+
+    /// 物理値を文字列で表現するときの小数点以下の桁数を返却する。
+    pub fn decimal_places(&self) -> usize {
+        self.data_value_factor.max(0) as usize
+    }
+}
+
+impl Section5 {
+    /// 物理値を文字列で表現するときの小数点以下の桁数を返却する。
+    pub fn decimal_places(&self) -> usize {
+        match &self.data_representation {
+            DataRepresentation::SimplePacking(packing) => packing.decimal_places(),
+            DataRepresentation::ComplexPacking(packing) => packing.simple_packing.decimal_places(),
+            DataRepresentation::RunLength(run_length) => run_length.decimal_places(),
+        }
+    }
+}
+
+/// 第5節を読み込んで、第5節の情報を返却する。
 ///
 /// ファイルポインタが、第5節の開始位置にあることを想定している。
 /// 関数終了後、ファイルポインタは第6節の開始位置に移動する。
-fn read_section5(reader: &mut FileReader) -> anyhow::Result<Section5> {
+fn read_section5<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Section5> {
+    let start = reader.stream_position()?;
     // 節の長さ
     let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of section 5"))?;
     // 節番号
@@ -776,7 +2793,191 @@ fn read_section5(reader: &mut FileReader) -> anyhow::Result<Section5> {
     // 全資料点の数
     let number_of_points = read_section5_number_of_points(reader)?;
     // 資料表現テンプレート番号
-    read_section5_document_expression_template(reader)?;
+    let template = read_section5_document_expression_template(reader)?;
+    // 資料表現テンプレート
+    let data_representation = match template {
+        DOCUMENT_EXPRESSION_TEMPLATE_SIMPLE_PACKING => {
+            DataRepresentation::SimplePacking(read_section5_simple_packing(reader)?)
+        }
+        DOCUMENT_EXPRESSION_TEMPLATE_COMPLEX_PACKING => {
+            DataRepresentation::ComplexPacking(read_section5_complex_packing(reader, false)?)
+        }
+        DOCUMENT_EXPRESSION_TEMPLATE_SPATIAL_DIFFERENCING => {
+            DataRepresentation::ComplexPacking(read_section5_complex_packing(reader, true)?)
+        }
+        _ => DataRepresentation::RunLength(read_section5_run_length(reader, length)?),
+    };
+    // 次の節の開始位置に移動
+    reader.seek(SeekFrom::Start(start + length as u64))?;
+
+    Ok(Section5 {
+        number_of_points,
+        template,
+        data_representation,
+    })
+}
+
+/// 第5節 全資料点の数を読み込んで、返却する。
+fn read_section5_number_of_points<R: Read>(reader: &mut R) -> anyhow::Result<u32> {
+    // 第5節 節番号: 1byte
+    read_u32(reader).map_err(|_| anyhow!("failed to read a number of points in section 5"))
+}
+
+/// 第5節 資料表現テンプレート番号を読み込み、対応している資料表現テンプレート番号であることを確認する。
+fn read_section5_document_expression_template<R: Read>(reader: &mut R) -> anyhow::Result<u16> {
+    let value =
+        read_u16(reader).map_err(|_| anyhow!("failed to read a document expression template"))?;
+    match value {
+        DOCUMENT_EXPRESSION_TEMPLATE_SIMPLE_PACKING
+        | DOCUMENT_EXPRESSION_TEMPLATE_COMPLEX_PACKING
+        | DOCUMENT_EXPRESSION_TEMPLATE_SPATIAL_DIFFERENCING
+        | DOCUMENT_EXPRESSION_TEMPLATE_RUN_LENGTH => Ok(value),
+        _ => Err(anyhow!(
+            "a document expression template {value} is not supported"
+        )),
+    }
+}
+
+/// 第5節 資料表現テンプレート5.0（格子点資料－単純圧縮）を読み込んで、返却する。
+fn read_section5_simple_packing<R: Read>(reader: &mut R) -> anyhow::Result<SimplePacking> {
+    // 参照値（R）
+    let reference_value = read_u32(reader)
+        .map(f32::from_bits)
+        .map_err(|_| anyhow!("failed to read a reference value"))?;
+    // 二進尺度因子（E）
+    let binary_scale_factor = read_u16(reader)
+        .map(sign_magnitude_i16)
+        .map_err(|_| anyhow!("failed to read a binary scale factor"))?;
+    // 十進尺度因子（D）
+    let decimal_scale_factor = read_u16(reader)
+        .map(sign_magnitude_i16)
+        .map_err(|_| anyhow!("failed to read a decimal scale factor"))?;
+    // 1データのビット数
+    let bits_per_value = read_section5_bits_per_value(reader)?;
+    // 元の資料値の種類
+    let original_field_type =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a type of original field values"))?;
+
+    Ok(SimplePacking {
+        reference_value,
+        binary_scale_factor,
+        decimal_scale_factor,
+        bits_per_value,
+        original_field_type,
+    })
+}
+
+/// 第5節 資料表現テンプレート5.2（格子点資料－複合圧縮）または5.3（格子点資料－複合圧縮と空間差分）を
+/// 読み込んで、返却する。
+///
+/// # 引数
+///
+/// * `reader` - リーダー。
+/// * `with_spatial_differencing` - 資料表現テンプレート5.3の場合は`true`。
+fn read_section5_complex_packing<R: Read>(
+    reader: &mut R,
+    with_spatial_differencing: bool,
+) -> anyhow::Result<ComplexPacking> {
+    // 参照値、尺度因子、グループの参照値のビット数及び元の資料値の種類
+    let simple_packing = read_section5_simple_packing(reader)?;
+    // グループ分割の方法
+    let group_splitting_method =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a group splitting method"))?;
+    // 欠測値の管理
+    let missing_value_management = read_u8(reader)
+        .map_err(|_| anyhow!("failed to read a missing value management"))
+        .and_then(validate_missing_value_management)?;
+    // 第一欠測値の代替値
+    let primary_missing_value_substitute = read_u32(reader)
+        .map_err(|_| anyhow!("failed to read a primary missing value substitute"))?;
+    // 第二欠測値の代替値
+    let secondary_missing_value_substitute = read_u32(reader)
+        .map_err(|_| anyhow!("failed to read a secondary missing value substitute"))?;
+    // グループの数
+    let number_of_groups =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a number of groups"))?;
+    // グループの幅の参照値
+    let group_width_reference =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a reference for group widths"))?;
+    // グループの幅のビット数
+    let group_width_bits =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a number of bits for group widths"))?;
+    // グループの長さの参照値
+    let group_length_reference =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a reference for group lengths"))?;
+    // グループの長さの増分
+    let group_length_increment =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a length increment"))?;
+    // 最後のグループの真の長さ
+    let last_group_length =
+        read_u32(reader).map_err(|_| anyhow!("failed to read a true length of the last group"))?;
+    // 尺度付きグループの長さのビット数
+    let group_length_bits = read_u8(reader)
+        .map_err(|_| anyhow!("failed to read a number of bits for group lengths"))?;
+    // 空間差分の次数及び追加記述子のオクテット数
+    let spatial_differencing = if with_spatial_differencing {
+        Some(read_section5_spatial_differencing(reader)?)
+    } else {
+        None
+    };
+
+    Ok(ComplexPacking {
+        simple_packing,
+        group_splitting_method,
+        missing_value_management,
+        primary_missing_value_substitute,
+        secondary_missing_value_substitute,
+        number_of_groups,
+        group_width_reference,
+        group_width_bits,
+        group_length_reference,
+        group_length_increment,
+        last_group_length,
+        group_length_bits,
+        spatial_differencing,
+    })
+}
+
+/// 第5節 空間差分の次数と追加記述子のオクテット数を読み込んで、対応している値であるか確認する。
+fn read_section5_spatial_differencing<R: Read>(
+    reader: &mut R,
+) -> anyhow::Result<SpatialDifferencing> {
+    let order =
+        read_u8(reader).map_err(|_| anyhow!("failed to read an order of spatial differencing"))?;
+    if !(1..=2).contains(&order) {
+        return Err(anyhow!(
+            "an order of spatial differencing {order} is not supported"
+        ));
+    }
+    let number_of_octets = read_u8(reader)
+        .map_err(|_| anyhow!("failed to read a number of octets of extra descriptors"))?;
+    if !(1..=8).contains(&number_of_octets) {
+        return Err(anyhow!(
+            "a number of octets of extra descriptors must be between 1 and 8 (octets:{number_of_octets})"
+        ));
+    }
+
+    Ok(SpatialDifferencing {
+        order,
+        number_of_octets,
+    })
+}
+
+/// 第5節 単純圧縮の1データのビット数を読み込み、対応しているビット数であることを確認する。
+///
+/// 1データのビット数が0の場合、すべての資料値は参照値と等しい。
+fn read_section5_bits_per_value<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
+    let value = read_u8(reader).map_err(|_| anyhow!("failed to read a bits per value"))?;
+    match value {
+        0..=MAX_BITS_PER_PACKED_VALUE => Ok(value),
+        _ => Err(anyhow!(
+            "a bits per value is greater than {MAX_BITS_PER_PACKED_VALUE}"
+        )),
+    }
+}
+
+/// 第5節 資料表現テンプレート5.200（ランレングス圧縮）を読み込んで、返却する。
+fn read_section5_run_length<R: Read>(reader: &mut R, length: u32) -> anyhow::Result<RunLength> {
     // 1データのビット数
     let bits_per_data = read_section5_bits_per_data(reader)?;
     // 今回の圧縮に用いたレベルの最大値
@@ -784,90 +2985,184 @@ fn read_section5(reader: &mut FileReader) -> anyhow::Result<Section5> {
     // レベルの私大値
     let max_level = read_section5_max_level(reader)?;
     // データ代表値の尺度因子
-    read_section5_data_value_factor(reader)?;
+    let data_value_factor = read_section5_data_value_factor(reader)?;
     // レベルmに対応するデータ代表値
-    let remaining_length = (length - (4 + 1 + 4 + 2 + 1 + 2 + 2 + 1)) as u16;
+    let remaining_length = length
+        .checked_sub(4 + 1 + 4 + 2 + 1 + 2 + 2 + 1)
+        .ok_or_else(|| anyhow!("length of section 5 is too short (length:{length})"))?;
     let number_of_levels = remaining_length / 2;
     let mut level_values = Vec::new();
     for _ in 0..number_of_levels {
         level_values.push(read_u16(reader).map_err(|_| anyhow!("failed to read a level value"))?);
     }
+    if number_of_levels < max_level_at_file as u32 {
+        return Err(anyhow!(
+            "a number of level values is less than a max level of this time (levels:{number_of_levels}, max level:{max_level_at_file})"
+        ));
+    }
 
-    Ok(Section5 {
-        number_of_points,
+    Ok(RunLength {
         bits_per_data,
         max_level_at_file,
         max_level,
+        data_value_factor,
         level_values,
     })
 }
 
-/// 第5節 全資料点の数を読み込んで、返却する。
-fn read_section5_number_of_points(reader: &mut FileReader) -> anyhow::Result<u32> {
-    // 第5節 節番号: 1byte
-    read_u32(reader).map_err(|_| anyhow!("failed to read a number of points in section 5"))
-}
-
-/// 第5節 資料表現テンプレート番号を読み込み、想定している資料表現テンプレート番号であることを確認する。
-fn read_section5_document_expression_template(reader: &mut FileReader) -> anyhow::Result<()> {
-    let value =
-        read_u16(reader).map_err(|_| anyhow!("failed to read a document expression template"))?;
-    match value {
-        DOCUMENT_EXPRESSION_TEMPLATE => Ok(()),
-        _ => Err(anyhow!(
-            "a document expression template is not {DOCUMENT_EXPRESSION_TEMPLATE}"
-        )),
-    }
-}
-
 /// 第5節 1データのビット数を読み込み、想定しているビット数であることを確認する。
-fn read_section5_bits_per_data(reader: &mut FileReader) -> anyhow::Result<u8> {
+fn read_section5_bits_per_data<R: Read>(reader: &mut R) -> anyhow::Result<u8> {
     let value = read_u8(reader).map_err(|_| anyhow!("failed to read a bits per data"))?;
     match value {
-        BITS_PER_DATA => Ok(value),
-        _ => Err(anyhow!("a bits per data is not {BITS_PER_DATA}")),
+        1..=MAX_BITS_PER_DATA => Ok(value),
+        _ => Err(anyhow!(
+            "a bits per data is not between 1 and {MAX_BITS_PER_DATA}"
+        )),
     }
 }
 
 /// 第5節 今回の圧縮に用いたレベルの最大値を読み込み、返却する。
-fn read_section5_max_level_of_this_time(reader: &mut FileReader) -> anyhow::Result<u16> {
+fn read_section5_max_level_of_this_time<R: Read>(reader: &mut R) -> anyhow::Result<u16> {
     read_u16(reader).map_err(|_| anyhow!("failed to read a max level of this time"))
 }
 
 /// 第5節 レベルの最大値を読み込み、返却する。
-fn read_section5_max_level(reader: &mut FileReader) -> anyhow::Result<u16> {
+fn read_section5_max_level<R: Read>(reader: &mut R) -> anyhow::Result<u16> {
     read_u16(reader).map_err(|_| anyhow!("failed to read a max level"))
 }
 
-/// 第5節 データ代表値の尺度因子を読み込み、想定している尺度因子であることを確認する。
-fn read_section5_data_value_factor(reader: &mut FileReader) -> anyhow::Result<()> {
-    let value = read_u8(reader).map_err(|_| anyhow!("failed to read a data value factor"))?;
-    match value {
-        DATA_VALUE_FACTOR => Ok(()),
-        _ => Err(anyhow!("a data value factor is not {DATA_VALUE_FACTOR}")),
+/// 第5節 データ代表値の尺度因子を読み込み、返却する。
+///
+/// 尺度因子は、最上位ビットを符号とした符号付き整数として記録されている。
+fn read_section5_data_value_factor<R: Read>(reader: &mut R) -> anyhow::Result<i8> {
+    read_u8(reader)
+        .map(sign_magnitude_i8)
+        .map_err(|_| anyhow!("failed to read a data value factor"))
+}
+
+/// 第6節 ビットマップ指示符: ビットマップを適用する
+const BITMAP_INDICATOR_DEFINED: u8 = 0;
+/// 第6節 ビットマップ指示符: 以前に定義されたビットマップを適用する
+const BITMAP_INDICATOR_PREVIOUSLY_DEFINED: u8 = 254;
+/// 第6節 ビットマップ指示符: ビットマップを適用しない
+const BITMAP_INDICATOR_NONE: u8 = 255;
+
+/// 第6節情報
+#[derive(Debug, Clone)]
+pub struct Section6 {
+    /// ビットマップ指示符（符号表6.0）
+    pub bitmap_indicator: u8,
+}
+
+/// 第6節に記録されているビットマップ
+#[derive(Debug, Clone, Copy)]
+struct Bitmap {
+    /// ファイルの先頭からビットマップの先頭までのバイト数
+    offset: u64,
+    /// ビットマップのバイト数
+    length: u32,
+    /// ビットマップを適用する全格子点の数
+    number_of_points: u32,
+    /// ビットマップのビットが1の格子点の数
+    number_of_values: u32,
+}
+
+impl Bitmap {
+    /// ファイルからビットマップを読み込んで、返却する。
+    fn read<R: Read + Seek>(&self, reader: &mut R) -> anyhow::Result<Vec<u8>> {
+        reader.seek(SeekFrom::Start(self.offset))?;
+        let mut bitmap = vec![0; self.length as usize];
+        reader
+            .read_exact(&mut bitmap)
+            .map_err(|_| anyhow!("failed to read a bitmap"))?;
+
+        Ok(bitmap)
     }
 }
 
-/// 第6節を読み込んで、確認する。
+/// 第6節を読み込んで、第6節の情報と適用するビットマップを返却する。
 ///
-/// ファイルポインタが、第5節の開始位置にあることを想定している。
-/// 関数終了後、ファイルポインタは第6節の開始位置に移動する。
-fn read_section6(reader: &mut FileReader) -> anyhow::Result<()> {
-    // 節の長さ: 4bytes
-    reader.seek_relative(4)?;
+/// ファイルポインタが、第6節の開始位置にあることを想定している。
+/// 関数終了後、ファイルポインタは第7節の開始位置に移動する。
+///
+/// # 引数
+///
+/// * `reader` - リーダー。
+/// * `number_of_points` - 全格子点の数。
+/// * `previous` - 同じGRIB2報で以前に定義されたビットマップ。ビットマップ指示符が254の場合に適用する。
+fn read_section6<R: Read + Seek>(
+    reader: &mut R,
+    number_of_points: u32,
+    previous: Option<Bitmap>,
+) -> anyhow::Result<(Section6, Option<Bitmap>)> {
+    let start = reader.stream_position()?;
+    // 節の長さ
+    let length = read_u32(reader).map_err(|_| anyhow!("failed to read length of section 6"))?;
     // 節番号
     let section_number =
         read_u8(reader).map_err(|_| anyhow!("failed to read section number at section 6"))?;
     if section_number != 6 {
         return Err(anyhow!("section number is miss match in section 6"));
     }
-
     // ビットマップ指示符
-    reader.seek_relative(1).map_err(|e| e.into())
+    let bitmap_indicator =
+        read_u8(reader).map_err(|_| anyhow!("failed to read a bitmap indicator"))?;
+    let bitmap = match bitmap_indicator {
+        BITMAP_INDICATOR_DEFINED => Some(read_section6_bitmap(reader, length, number_of_points)?),
+        BITMAP_INDICATOR_PREVIOUSLY_DEFINED => {
+            let bitmap =
+                previous.ok_or_else(|| anyhow!("a previously defined bitmap does not exist"))?;
+            if bitmap.number_of_points != number_of_points {
+                return Err(anyhow!(
+                    "the number of points is different from a previously defined bitmap (bitmap:{}, points:{number_of_points})",
+                    bitmap.number_of_points
+                ));
+            }
+            Some(bitmap)
+        }
+        BITMAP_INDICATOR_NONE => None,
+        _ => {
+            return Err(anyhow!(
+                "a bitmap indicator {bitmap_indicator} is not supported"
+            ))
+        }
+    };
+    // 次の節の開始位置に移動
+    reader.seek(SeekFrom::Start(start + length as u64))?;
+
+    Ok((Section6 { bitmap_indicator }, bitmap))
+}
+
+/// 第6節 ビットマップを読み込んで、ビットマップの位置とビットが1の格子点の数を返却する。
+fn read_section6_bitmap<R: Read + Seek>(
+    reader: &mut R,
+    length: u32,
+    number_of_points: u32,
+) -> anyhow::Result<Bitmap> {
+    let offset = reader.stream_position()?;
+    let length = length
+        .checked_sub(4 + 1 + 1)
+        .ok_or_else(|| anyhow!("length of section 6 is too short"))?;
+    if (length as u64) * 8 < number_of_points as u64 {
+        return Err(anyhow!(
+            "a bitmap is shorter than the number of points (bitmap bytes:{length}, points:{number_of_points})"
+        ));
+    }
+    let mut bitmap = vec![0; length as usize];
+    reader
+        .read_exact(&mut bitmap)
+        .map_err(|_| anyhow!("failed to read a bitmap"))?;
+
+    Ok(Bitmap {
+        offset,
+        length,
+        number_of_points,
+        number_of_values: count_defined_points(&bitmap, number_of_points),
+    })
 }
 
 /// 第8節を読み込んで、確認する。
-fn read_section8(reader: &mut FileReader) -> anyhow::Result<()> {
+fn read_section8<R: Read>(reader: &mut R) -> anyhow::Result<()> {
     let mut buf = [0; 4];
     let size = reader
         .read(&mut buf)
@@ -882,6 +3177,113 @@ fn read_section8(reader: &mut FileReader) -> anyhow::Result<()> {
     }
 }
 
+/// ランレングス圧縮オクテット列を、1セットのランレングス符号化ごとに順番に展開するデコーダー
+///
+/// 1セットの終わりは、次のセットのレベル値を読み込んだ時点で判明するため、読み込んだ次のセットのレベル値を保持する。
+struct RunLengthDecoder {
+    /// 1データのビット数
+    nbit: u8,
+    /// 今回の圧縮に用いたレベルの最大値
+    maxv: u16,
+    /// ランレングス値の進数
+    lngu: u16,
+    /// 読み込んでいないレベル値またはランレングス値の数
+    remaining_tokens: u64,
+    /// 資料点数
+    number_of_points: u32,
+    /// 展開した格子点の数
+    number_of_read: u32,
+    /// 読み込んだ次のセットのレベル値
+    pending: Option<u16>,
+    /// 展開中の1セットのランレングス符号化
+    run_length: Vec<u16>,
+}
+
+impl RunLengthDecoder {
+    /// デコーダーを作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `length` - ランレングス圧縮オクテット列のバイト数。
+    /// * `nbit` - 1データのビット数（第5節 12オクテット）。
+    /// * `maxv` - 今回の圧縮に用いたレベルの最大値（第5節 13-14オクテット）。
+    /// * `number_of_points` - 資料点数。
+    fn new(length: u64, nbit: u8, maxv: u16, number_of_points: u32) -> anyhow::Result<Self> {
+        if nbit == 0 || MAX_BITS_PER_DATA < nbit {
+            return Err(anyhow!(
+                "a bits per data must be between 1 and {MAX_BITS_PER_DATA} (nbit:{nbit})"
+            ));
+        }
+        let max_token = (1u32 << nbit) - 1;
+        if max_token <= maxv as u32 {
+            return Err(anyhow!(
+                "a max level must be less than {max_token} (nbit:{nbit}, maxv:{maxv})"
+            ));
+        }
+
+        Ok(Self {
+            nbit,
+            maxv,
+            lngu: (max_token - maxv as u32) as u16,
+            remaining_tokens: length * 8 / nbit as u64,
+            number_of_points,
+            number_of_read: 0,
+            pending: None,
+            run_length: Vec::new(),
+        })
+    }
+
+    /// 次の1セットのランレングス符号化を展開して、レベル値とそのレベル値が連続する数を返却する。
+    ///
+    /// すべての格子点を展開した場合は`None`を返却する。
+    fn next<B: AsRef<[u8]>>(
+        &mut self,
+        bits: &mut BitReader<B>,
+    ) -> anyhow::Result<Option<(u16, u32)>> {
+        // 前回読み込んだレベル値から、次のセットを開始
+        self.run_length.clear();
+        self.run_length.extend(self.pending.take());
+        while 0 < self.remaining_tokens {
+            let value = bits.read(self.nbit as u32)? as u16;
+            self.remaining_tokens -= 1;
+            if value <= self.maxv && !self.run_length.is_empty() {
+                let expanded = self.expand()?;
+                // すべての格子点を展開した場合、残りは詰め物
+                if self.number_of_read < self.number_of_points {
+                    self.pending = Some(value);
+                } else {
+                    self.remaining_tokens = 0;
+                }
+                return Ok(Some(expanded));
+            }
+            self.run_length.push(value);
+        }
+        if self.run_length.is_empty() {
+            return Ok(None);
+        }
+
+        self.expand().map(Some)
+    }
+
+    /// 展開中の1セットのランレングス符号化を展開して、展開した格子点の数を数える。
+    ///
+    /// 展開した格子点の数が資料点数を超える場合はエラーを返却する。
+    fn expand(&mut self) -> anyhow::Result<(u16, u32)> {
+        let (level, count) = expand_run_length(&self.run_length, self.maxv, self.lngu)?;
+        let remaining = self.number_of_points - self.number_of_read;
+        if (remaining as u64) < count {
+            return Err(anyhow!(
+                "the run length exceeds the number of points (run length:{count}, remaining points:{remaining})"
+            ));
+        }
+        let count = count as u32;
+        self.number_of_read += count;
+        self.run_length.clear();
+
+        Ok((level, count))
+    }
+}
+
 /// 1セットのランレングス符号化（圧縮）を展開する。
 ///
 /// valuesの最初の要素はレベル値で、それ以降はランレングス値である。
@@ -941,67 +3343,180 @@ fn read_section8(reader: &mut FileReader) -> anyhow::Result<()> {
 /// # 戻り値
 ///
 /// レベル値とそのレベル値を繰り返す数を格納したタプル。
-fn expand_run_length(values: &[u16], maxv: u16, lngu: u16) -> (u16, u32) {
-    assert!(values[0] <= maxv, "values[0]={}, maxv={}", values[0], maxv);
+/// 最初の要素がレベル値でない場合、または繰り返す数が64ビットで表現できない場合はエラーを返却する。
+fn expand_run_length(values: &[u16], maxv: u16, lngu: u16) -> anyhow::Result<(u16, u64)> {
+    // セットの最初の値がランレングス値の場合、レベル値が存在しない
+    if maxv < values[0] {
+        return Err(anyhow!(
+            "a set of run length must start with a level value (value:{}, maxv:{maxv})",
+            values[0]
+        ));
+    }
 
     // ランレングス圧縮されていない場合
     if values.len() == 1 {
-        return (values[0], 1);
+        return Ok((values[0], 1));
     }
 
     // ランレングス圧縮を展開
-    let values: Vec<u32> = values.iter().map(|v| *v as u32).collect();
-    let lngu = lngu as u32;
-    let maxv = maxv as u32;
-    let count: u32 = values[1..]
-        .iter()
-        .enumerate()
-        .map(|(i, &v)| lngu.pow(i as u32) * (v - (maxv + 1)))
-        .sum();
+    // NBITが大きい場合にLNGUのべき乗が32ビットを超えるため、64ビットで計算
+    let lngu = lngu as u64;
+    let maxv = maxv as u64;
+    let mut count = 1u64;
+    let mut weight = Some(1u64); // LNGU進数の桁の重み（64ビットを超える場合は`None`）
+    for &v in &values[1..] {
+        let digit = v as u64 - (maxv + 1);
+        if digit != 0 {
+            count = weight
+                .and_then(|weight| weight.checked_mul(digit))
+                .and_then(|run| count.checked_add(run))
+                .ok_or_else(|| anyhow!("the run length overflows (level:{})", values[0]))?;
+        }
+        weight = weight.and_then(|weight| weight.checked_mul(lngu));
+    }
 
-    (values[0] as u16, count + 1)
+    Ok((values[0], count))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::BufReader;
+    use std::ops::Range;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use super::*;
 
-    const SAMPLE_FILE: &'static str = "fixtures/sample.bin";
+    const SAMPLE_FILE: &str = "fixtures/sample.bin";
     const SAMPLE_MAX_LEVEL_THIS_TIME: u16 = 84;
+    const SAMPLE_TOTAL_LENGTH: u64 = 332_484;
+    /// サンプル・ファイルの第1節、第3節及び第4節のオクテット位置（ファイルの先頭から、0から数える）
+    const SAMPLE_SECTION1: Range<usize> = 16..37;
+    const SAMPLE_SECTION3: Range<usize> = 37..109;
+    const SAMPLE_SECTION4: Range<usize> = 109..191;
+    /// サンプル・ファイルの第5節の、節の長さと節番号を除いた内容のオクテット位置
+    const SAMPLE_SECTION5_BODY: Range<usize> = 196..404;
+    /// サンプル・ファイルの第7節の資料値の開始位置（資料値は第8節の直前まで）
+    const SAMPLE_SECTION7_DATA: usize = 415;
+
+    /// 第0節のGRIB報全体の長さのオクテット位置（節の先頭から、0から数える）
+    const SECTION0_TOTAL_LENGTH: Range<usize> = 8..16;
+    /// 第1節の作成ステータスと資料の種類のオクテット位置
+    const SECTION1_CREATION_STATUS: usize = 19;
+    const SECTION1_DOCUMENT_KIND: usize = 20;
+    /// 第3節の資料点数、地球の形状（地球球体の半径及び地球回転楕円体の軸の長さを含む）、
+    /// 緯線と経線に沿った格子点数、最初の格子点の緯度と経度のオクテット位置
+    const SECTION3_NUMBER_OF_POINTS: Range<usize> = 6..10;
+    const SECTION3_EARTH_SHAPE: Range<usize> = 14..30;
+    const SECTION3_NI: Range<usize> = 30..34;
+    const SECTION3_NJ: Range<usize> = 34..38;
+    const SECTION3_FIRST_LATITUDE: Range<usize> = 46..50;
+    const SECTION3_FIRST_LONGITUDE: Range<usize> = 50..54;
+
+    /// テストごとに異なる、一時ディレクトリ内のファイルのパスを返却する。
+    ///
+    /// 並列に実行されるテストや、同時に実行されたテスト・プロセスが、同じファイルを上書きしないように、
+    /// プロセスIDと通し番号をファイル名に付与する。
+    fn temp_path(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let number = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        std::env::temp_dir().join(format!("grib2csv_{}_{number}_{name}", std::process::id()))
+    }
 
     #[test]
     fn can_read_grib_file() {
         let mut reader = BufReader::new(File::open(SAMPLE_FILE).unwrap());
         // 第0節を読み込み
-        assert!(read_section0(&mut reader).is_ok());
+        let section0 = read_section0(&mut reader).unwrap();
+        assert_eq!(section0.document_domain, 0);
+        assert_eq!(section0.grib_version, 2);
+        assert_eq!(section0.total_length, SAMPLE_TOTAL_LENGTH);
 
         // 第1節を読み込み
-        assert!(read_section1(&mut reader).is_ok());
+        let section1 = read_section1(&mut reader, &ValidationPolicy::default()).unwrap();
+        assert_eq!(section1.center, 34);
+        assert_eq!(section1.sub_center, 0);
+        assert_eq!(section1.master_table_version, 2);
+        assert_eq!(section1.local_table_version, 1);
+        assert_eq!(section1.reference_time_significance, 0);
+        assert_eq!(
+            section1.referenced_at,
+            time::macros::datetime!(2021-08-17 09:00:00)
+        );
+        assert_eq!(section1.creation_status, 0);
+        assert_eq!(section1.document_kind, 0);
 
         // 第3節を読み込み
         let section3 = read_section3(&mut reader).unwrap();
         assert_eq!(section3.number_of_points, 2560 * 3360);
-        assert_eq!(section3.northernmost, 47995833);
-        assert_eq!(section3.westernmost, 118006250);
-        assert_eq!(section3.southernmost, 20004167);
-        assert_eq!(section3.easternmost, 149993750);
-        assert_eq!(section3.longitude_increment, 12500);
-        assert_eq!(section3.latitude_increment, 8333);
+        assert_eq!(section3.template, 0);
+        assert_eq!(section3.earth_shape.shape, 4);
+        assert_eq!(section3.earth_shape.semi_major_axis, 6378137.0);
+        assert_eq!(section3.number_of_points_along_parallel, 2560);
+        assert_eq!(section3.number_of_points_along_meridian, 3360);
+        assert_eq!(section3.first_latitude, 47995833);
+        assert_eq!(section3.first_longitude, 118006250);
+        assert_eq!(section3.coordinate(0), (118006250, 47995833));
+        assert_eq!(section3.coordinate(2559), (149993750, 47995833));
+        assert_eq!(section3.coordinate(2560), (118006250, 47987500));
+        assert_eq!(section3.scanning_mode, 0);
+        let GridDefinition::LatLon(grid) = &section3.grid_definition else {
+            panic!("the grid definition is not a lat/lon grid");
+        };
+        assert_eq!(grid.last_latitude, 20004167);
+        assert_eq!(grid.last_longitude, 149993750);
+        assert_eq!(grid.longitude_increment, 12500);
+        assert_eq!(grid.latitude_increment, 8333);
 
-        // 第4節を読み飛ばす
-        assert!(read_section4(&mut reader).is_ok());
+        // 第4節を読み込み
+        let section4 = read_section4(&mut reader).unwrap();
+        assert_eq!(section4.number_of_coordinates, 0);
+        assert_eq!(section4.template, 50008);
+        let product = section4.product_definition.unwrap();
+        assert_eq!(product.parameter_category, 1);
+        assert_eq!(product.parameter_number, 200);
+        assert_eq!(product.generating_process, 0);
+        assert_eq!(product.time_unit, 0);
+        assert_eq!(product.forecast_time, -60);
+        assert_eq!(product.forecast_duration(), Some(Duration::minutes(-60)));
+        assert_eq!(product.first_surface.surface_type, 1);
+        assert!(product.first_surface.scale_factor.is_none());
+        assert!(product.first_surface.scaled_value.is_none());
+        assert!(product.is_statistical());
+        let statistical = product.statistical_process.unwrap();
+        assert_eq!(
+            statistical.end_of_overall_time_interval,
+            time::macros::datetime!(2021-08-17 09:00:00)
+        );
+        assert_eq!(statistical.number_of_missing_values, 0);
+        assert_eq!(statistical.time_ranges.len(), 1);
+        assert_eq!(statistical.time_ranges[0].statistical_process, 1);
+        assert_eq!(
+            statistical.time_ranges[0].duration(),
+            Some(Duration::minutes(60))
+        );
+        assert!(product.operation_info.is_some());
 
         // 第5節を読み込み
         let section5 = read_section5(&mut reader).unwrap();
         assert_eq!(section5.number_of_points, 8601600);
-        assert_eq!(section5.bits_per_data, 8);
-        assert_eq!(section5.max_level_at_file, SAMPLE_MAX_LEVEL_THIS_TIME);
-        assert_eq!(section5.max_level, 98);
-        assert!(section5.max_level_at_file <= section5.max_level);
-        assert_eq!(section5.level_values, sample_level_values());
+        assert_eq!(section5.template, 200);
+        let DataRepresentation::RunLength(run_length) = &section5.data_representation else {
+            panic!("the data representation is not a run length");
+        };
+        assert_eq!(run_length.bits_per_data, 8);
+        assert_eq!(run_length.max_level_at_file, SAMPLE_MAX_LEVEL_THIS_TIME);
+        assert_eq!(run_length.max_level, 98);
+        assert_eq!(run_length.data_value_factor, 1);
+        assert!(run_length.max_level_at_file <= run_length.max_level);
+        assert_eq!(run_length.level_values, sample_level_values());
 
         // 第6節を読み込み
-        assert!(read_section6(&mut reader).is_ok());
+        let (section6, bitmap) =
+            read_section6(&mut reader, section3.number_of_points, None).unwrap();
+        assert_eq!(section6.bitmap_indicator, 255);
+        assert!(bitmap.is_none());
 
         // 第7節を読み込み
         // 節の長さ: 4bytes
@@ -1016,6 +3531,732 @@ mod tests {
         assert!(read_section8(&mut reader).is_ok());
     }
 
+    #[test]
+    fn can_read_multiple_messages() {
+        // サンプル・ファイルを2回連結して、2つのGRIB2報を記録したファイルを作成
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        let input = temp_path("multiple_messages.bin");
+        let output = temp_path("multiple_messages.csv");
+        std::fs::write(&input, [sample.as_slice(), sample.as_slice()].concat()).unwrap();
+
+        let grib2 = Grib2Csv::new(&input, true).unwrap();
+        assert_eq!(grib2.number_of_messages(), 2);
+        let offsets: Vec<u64> = grib2.messages().map(|m| m.offset()).collect();
+        assert_eq!(offsets, vec![0, SAMPLE_TOTAL_LENGTH]);
+        for message in grib2.messages() {
+            let metadata = message.metadata();
+            assert_eq!(metadata.section0.total_length, SAMPLE_TOTAL_LENGTH);
+            assert_eq!(
+                metadata.section1.referenced_at,
+                grib2.metadata().section1.referenced_at
+            );
+        }
+
+        // 2番目のGRIB2報を、最初のGRIB2報と独立して変換
+        let boundary = BoundaryBuilder::default()
+            .northernmost(Some(36_000_000))
+            .southernmost(Some(35_000_000))
+            .westernmost(Some(135_000_000))
+            .easternmost(Some(136_000_000))
+            .build();
+        grib2.convert_message(1, &output, boundary.clone()).unwrap();
+        let second = std::fs::read_to_string(&output).unwrap();
+        grib2.convert_message(0, &output, boundary.clone()).unwrap();
+        let first = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(first, second);
+        assert!(grib2.convert_message(2, &output, boundary).is_err());
+
+        std::fs::remove_file(input).unwrap();
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn reject_total_length_shorter_than_read_sections() {
+        let mut bytes = std::fs::read(SAMPLE_FILE).unwrap();
+        // GRIB報全体の長さが0の場合や、第6節までの長さより短い場合は、同じGRIB2報を繰り返し読み込まない
+        for total_length in [0u64, 16, 100] {
+            bytes[SECTION0_TOTAL_LENGTH].copy_from_slice(&total_length.to_be_bytes());
+            assert!(Grib2Csv::from_bytes(&bytes, true, ValidationPolicy::default()).is_err());
+        }
+    }
+
+    #[test]
+    fn can_read_from_bytes_and_any_reader() {
+        let bytes = std::fs::read(SAMPLE_FILE).unwrap();
+        let output = temp_path("from_bytes.csv");
+        let boundary = BoundaryBuilder::default()
+            .northernmost(Some(36_000_000))
+            .southernmost(Some(35_000_000))
+            .westernmost(Some(135_000_000))
+            .easternmost(Some(136_000_000))
+            .build();
+        Grib2Csv::new(SAMPLE_FILE, true)
+            .unwrap()
+            .convert(&output, boundary.clone())
+            .unwrap();
+        let expected = std::fs::read_to_string(&output).unwrap();
+
+        // バイト列から変換
+        let grib2 = Grib2Csv::from_bytes(&bytes, true, ValidationPolicy::default()).unwrap();
+        assert_eq!(grib2.number_of_messages(), 1);
+        grib2.convert(&output, boundary.clone()).unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        // 所有権を持つバッファを包んだリーダーから変換
+        let grib2 = Grib2Csv::from_reader(
+            BufReader::new(Cursor::new(bytes)),
+            true,
+            ValidationPolicy::default(),
+        )
+        .unwrap();
+        grib2.convert(&output, boundary).unwrap();
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        std::fs::remove_file(output).unwrap();
+
+        // GRIB2報を含まないバイト列
+        assert!(Grib2Csv::from_bytes(&[], true, ValidationPolicy::default()).is_err());
+    }
+
+    /// 節の長さと節番号を、節の内容の前に付与する。
+    fn section(number: u8, body: &[u8]) -> Vec<u8> {
+        let length = (4 + 1 + body.len()) as u32;
+        [&length.to_be_bytes()[..], &[number], body].concat()
+    }
+
+    /// サンプル・ファイルの第1節から第4節を利用して、格子点数と第5節から第7節を置き換えたGRIB2報を作成する。
+    ///
+    /// 第3節の最初の格子点と増分は、サンプル・ファイルと同じである。
+    fn build_message(
+        ni: u32,
+        nj: u32,
+        section5: &[u8],
+        section6: &[u8],
+        section7: &[u8],
+    ) -> Vec<u8> {
+        build_message_with_section3(&sample_section3(ni, nj), section5, section6, section7)
+    }
+
+    /// サンプル・ファイルの第3節の格子点数を置き換えた第3節を返却する。
+    fn sample_section3(ni: u32, nj: u32) -> Vec<u8> {
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        let mut section3 = sample[SAMPLE_SECTION3].to_vec();
+        section3[SECTION3_NUMBER_OF_POINTS].copy_from_slice(&(ni * nj).to_be_bytes());
+        section3[SECTION3_NI].copy_from_slice(&ni.to_be_bytes());
+        section3[SECTION3_NJ].copy_from_slice(&nj.to_be_bytes());
+
+        section3
+    }
+
+    /// サンプル・ファイルの第3節の格子点数と、最初の格子点の緯度と経度（10^6度単位）を置き換えた第3節を返却する。
+    fn sample_section3_from(
+        ni: u32,
+        nj: u32,
+        first_latitude: i32,
+        first_longitude: i32,
+    ) -> Vec<u8> {
+        let mut section3 = sample_section3(ni, nj);
+        section3[SECTION3_FIRST_LATITUDE].copy_from_slice(&sign_magnitude(first_latitude));
+        section3[SECTION3_FIRST_LONGITUDE].copy_from_slice(&sign_magnitude(first_longitude));
+
+        section3
+    }
+
+    /// 最上位ビットを符号とする4オクテットの整数を返却する。
+    fn sign_magnitude(value: i32) -> [u8; 4] {
+        let sign = if value < 0 { 0x8000_0000 } else { 0 };
+
+        (sign | value.unsigned_abs()).to_be_bytes()
+    }
+
+    /// 参照値0.0、二進尺度因子0、十進尺度因子0、元の資料値の種類0（浮動小数点）の、
+    /// 単純圧縮（資料表現テンプレート5.0）の第5節の内容（節の長さと節番号を除く）を返却する。
+    fn simple_packing_section5(number_of_values: u32, bits_per_value: u8) -> Vec<u8> {
+        [
+            &number_of_values.to_be_bytes()[..],
+            &0u16.to_be_bytes(),
+            &0f32.to_bits().to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &[bits_per_value, 0],
+        ]
+        .concat()
+    }
+
+    /// 参照値250.0、二進尺度因子-1、十進尺度因子1、1データのビット数4、元の資料値の種類0（浮動小数点）の、
+    /// 単純圧縮の第5節の内容（節の長さと節番号を除く）を返却する。
+    ///
+    /// 圧縮された値Xの物理値は、(250.0 + X * 2^-1) / 10^1（小数点以下2桁）になる。
+    fn scaled_simple_packing_section5(number_of_values: u32) -> Vec<u8> {
+        [
+            &number_of_values.to_be_bytes()[..],
+            &0u16.to_be_bytes(),
+            &250f32.to_bits().to_be_bytes(),
+            &0x8001u16.to_be_bytes(),
+            &1u16.to_be_bytes(),
+            &[4, 0],
+        ]
+        .concat()
+    }
+
+    /// 3×2の格子の2番目の格子点をビットマップで除外した、単純圧縮のGRIB2報の資料値（圧縮された値: 0, 1, 2, 3, 15）
+    const BITMAP_SIMPLE_PACKING_DATA: [u8; 3] = [0x01, 0x23, 0xf0];
+
+    /// 3×2の格子の2番目の格子点をビットマップで除外した、単純圧縮のGRIB2報を返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `section7` - 第7節の資料値（通常は`BITMAP_SIMPLE_PACKING_DATA`）。
+    fn bitmap_simple_packing_message(section7: &[u8]) -> Vec<u8> {
+        build_message(
+            3,
+            2,
+            &scaled_simple_packing_section5(5),
+            &[0, 0b1011_1100],
+            section7,
+        )
+    }
+
+    /// 第1節以降の節を連結して、第0節と第8節を付与したGRIB2報を返却する。
+    fn build_grib2(sections: &[&[u8]]) -> Vec<u8> {
+        let body = [sections.concat().as_slice(), b"7777"].concat();
+        let total_length = (16 + body.len()) as u64;
+
+        [&b"GRIB\0\0\0\x02"[..], &total_length.to_be_bytes(), &body].concat()
+    }
+
+    /// サンプルファイルの第1節と第4節に、指定した第3節、第5節、第6節及び第7節を組み合わせたGRIB2報を返却する。
+    fn build_message_with_section3(
+        section3: &[u8],
+        section5: &[u8],
+        section6: &[u8],
+        section7: &[u8],
+    ) -> Vec<u8> {
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        build_grib2(&[
+            &sample[SAMPLE_SECTION1],
+            section3,
+            &sample[SAMPLE_SECTION4],
+            &section(5, section5),
+            &section(6, section6),
+            &section(7, section7),
+        ])
+    }
+
+    /// メモリ上のGRIB2報全体を変換し、GRIB2報のメタデータとCSVファイルの内容を返却する。
+    fn convert_bytes(name: &str, bytes: &[u8]) -> (Grib2Metadata, String) {
+        let output = temp_path(&format!("{name}.csv"));
+        let grib2 = Grib2Csv::from_bytes(bytes, false, ValidationPolicy::default()).unwrap();
+        grib2.convert(&output, Boundary::default()).unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(output).unwrap();
+
+        (grib2.metadata().clone(), csv)
+    }
+
+    #[test]
+    fn can_read_repeated_sections() {
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        let (section1, section4) = (&sample[SAMPLE_SECTION1], &sample[SAMPLE_SECTION4]);
+        let section5 =
+            |number_of_values: u32| section(5, &simple_packing_section5(number_of_values, 8));
+        // 第3節から第7節の後に、同じ格子の第4節から第7節と、異なる格子の第3節から第7節を繰り返し記録する。
+        // 2番目の資料は、最初の資料で定義したビットマップを適用する
+        let bytes = build_grib2(&[
+            section1,
+            &sample_section3(3, 2),
+            section4,
+            &section5(5),
+            &section(6, &[0, 0b1111_1000]),
+            &section(7, &[1, 2, 3, 4, 5]),
+            section4,
+            &section5(5),
+            &section(6, &[254]),
+            &section(7, &[5, 4, 3, 2, 1]),
+            &sample_section3(2, 1),
+            section4,
+            &section5(2),
+            &section(6, &[255]),
+            &section(7, &[7, 8]),
+        ]);
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        assert_eq!(grib2.number_of_messages(), 3);
+        assert!(grib2.messages().all(|message| message.offset() == 0));
+        let grid = grib2.grid_message(1).unwrap();
+        assert_eq!(grid.values()[..5], [5.0, 4.0, 3.0, 2.0, 1.0]);
+        assert!(grid.values()[5].is_nan());
+        let grid = grib2.grid_message(0).unwrap();
+        assert_eq!(grid.values()[..5], [1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert!(grid.values()[5].is_nan());
+        let grid = grib2.grid_message(2).unwrap();
+        assert_eq!((grid.ni(), grid.nj()), (2, 1));
+        assert_eq!(grid.values(), [7.0, 8.0]);
+        let values: Vec<Option<f64>> = grib2
+            .points_message(1)
+            .unwrap()
+            .map(|point| point.unwrap().value)
+            .collect();
+        assert_eq!(
+            values,
+            [Some(5.0), Some(4.0), Some(3.0), Some(2.0), Some(1.0), None]
+        );
+
+        // 第5節が欠けている資料や、第3節より前の第4節はエラー
+        let bytes = build_grib2(&[
+            section1,
+            &sample_section3(2, 1),
+            section4,
+            &section(6, &[255]),
+            &section(7, &[7, 8]),
+        ]);
+        assert!(Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).is_err());
+        let bytes = build_grib2(&[
+            section1,
+            section4,
+            &section5(2),
+            &section(6, &[255]),
+            &section(7, &[7, 8]),
+        ]);
+        assert!(Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).is_err());
+    }
+
+    #[test]
+    fn can_convert_simple_packing() {
+        let section5 = scaled_simple_packing_section5(6);
+        // 圧縮された値: 0, 1, 2, 3, 4, 15
+        let section7 = [0x01, 0x23, 0x4f];
+        let bytes = build_message(3, 2, &section5, &[255], &section7);
+
+        let (metadata, csv) = convert_bytes("grib2csv_simple_packing", &bytes);
+        let section5 = &metadata.section5;
+        assert_eq!(section5.template, 0);
+        let DataRepresentation::SimplePacking(packing) = &section5.data_representation else {
+            panic!("the data representation is not a simple packing");
+        };
+        assert_eq!(packing.reference_value, 250.0);
+        assert_eq!(packing.binary_scale_factor, -1);
+        assert_eq!(packing.decimal_scale_factor, 1);
+        assert_eq!(packing.bits_per_value, 4);
+        assert_eq!(section5.decimal_places(), 2);
+        let expected = "\
+118.006250,47.995833,25.00
+118.018750,47.995833,25.05
+118.031250,47.995833,25.10
+118.006250,47.987500,25.15
+118.018750,47.987500,25.20
+118.031250,47.987500,25.75
+";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn can_read_grid() {
+        let bytes = bitmap_simple_packing_message(&BITMAP_SIMPLE_PACKING_DATA);
+
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        let grid = grib2.grid().unwrap();
+        assert_eq!((grid.ni(), grid.nj()), (3, 2));
+        let rows: Vec<&[f64]> = grid.rows().collect();
+        assert_eq!(rows[1], [25.1, 25.15, 25.75]);
+        assert_eq!((rows[0][0], rows[0][2]), (25.0, 25.05));
+        // ビットマップで除外された格子点はNaN
+        assert!(rows[0][1].is_nan());
+        assert_eq!(grid.get(1, 0), None);
+        assert_eq!(grid.get(2, 1), Some(25.75));
+        assert_eq!(grid.coordinate(2, 1), (118031250, 47987500));
+        assert_eq!(grid.at(47987500, 118031250), Some(25.75));
+        assert_eq!(grid.at(47995833, 118018750), None);
+        assert!(grib2.grid_message(1).is_err());
+    }
+
+    #[test]
+    fn can_iterate_grid_points() {
+        let bytes = bitmap_simple_packing_message(&BITMAP_SIMPLE_PACKING_DATA);
+
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        let mut points = grib2.points().unwrap();
+        let first = points.next().unwrap().unwrap();
+        assert_eq!(
+            first,
+            GridPoint {
+                longitude: 118006250,
+                latitude: 47995833,
+                i: 0,
+                j: 0,
+                level: None,
+                value: Some(25.0),
+            }
+        );
+        // イテレーターがリーダーを借用している間は、変換できない
+        assert!(grib2.grid().is_err());
+        let rest: Vec<GridPoint> = points.map(|point| point.unwrap()).collect();
+        let values: Vec<(u32, u32, Option<f64>)> =
+            rest.iter().map(|p| (p.i, p.j, p.value)).collect();
+        assert_eq!(
+            values,
+            [
+                (1, 0, None),
+                (2, 0, Some(25.05)),
+                (0, 1, Some(25.1)),
+                (1, 1, Some(25.15)),
+                (2, 1, Some(25.75)),
+            ]
+        );
+        assert_eq!((rest[4].longitude, rest[4].latitude), (118031250, 47987500));
+        assert!(grib2.grid().is_ok());
+
+        // 資料値が不足している場合はエラー
+        let bytes = bitmap_simple_packing_message(&BITMAP_SIMPLE_PACKING_DATA[..2]);
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        assert!(grib2.points().is_err());
+    }
+
+    #[test]
+    fn grid_points_of_run_length_are_same_as_grid() {
+        let grib2 = Grib2Csv::new(SAMPLE_FILE, false).unwrap();
+        let grid = grib2.grid().unwrap();
+        let DataRepresentation::RunLength(run_length) =
+            &grib2.metadata().section5.data_representation
+        else {
+            panic!("the data representation is not a run length");
+        };
+        let mut number_of_points = 0;
+        let mut number_of_values = 0;
+        for point in grib2.points().unwrap() {
+            let point = point.unwrap();
+            assert_eq!(point.value, grid.get(point.i, point.j));
+            assert_eq!(
+                point.value,
+                run_length.representative_value(point.level.unwrap())
+            );
+            number_of_points += 1;
+            number_of_values += point.value.is_some() as usize;
+        }
+        assert_eq!(number_of_points, grid.values().len());
+        assert_eq!(
+            number_of_values,
+            grid.values().iter().filter(|v| !v.is_nan()).count()
+        );
+    }
+
+    #[test]
+    fn can_convert_complex_packing_with_spatial_differencing() {
+        // R = 0.0、E = 0、D = 1、グループの参照値のビット数4、元の資料値の種類0（浮動小数点）
+        // グループ分割の方法1、欠測値の管理1（第一欠測値を含む）、グループの数1
+        // グループの幅の参照値0、グループの幅のビット数2、グループの長さの参照値0、増分1、
+        // 最後のグループの真の長さ6、尺度付きグループの長さのビット数0
+        // 1次の空間差分、追加記述子のオクテット数2
+        let section5 = [
+            &6u32.to_be_bytes()[..],
+            &3u16.to_be_bytes(),
+            &0f32.to_bits().to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &1u16.to_be_bytes(),
+            &[4, 0, 1, 1],
+            &0u32.to_be_bytes(),
+            &0u32.to_be_bytes(),
+            &1u32.to_be_bytes(),
+            &[0, 2],
+            &0u32.to_be_bytes(),
+            &[1],
+            &6u32.to_be_bytes(),
+            &[0, 1, 2],
+        ]
+        .concat();
+        // 元の値: 250, 251, 欠測, 253, 255, 257（1次の空間差分: 1, 2, 2, 2）
+        // 追加記述子: 250、最小値1
+        // グループの参照値: 0、グループの幅: 2、グループの長さ: 6（最後のグループ）
+        // 圧縮された値（差分から最小値を引いた値）: 0, 0, 3（欠測）, 1, 1, 1
+        let section7 = [0x00, 0xfa, 0x00, 0x01, 0x00, 0x80, 0x0d, 0x50];
+        let bytes = build_message(3, 2, &section5, &[255], &section7);
+
+        let (metadata, csv) = convert_bytes("grib2csv_complex_packing", &bytes);
+        let section5 = &metadata.section5;
+        assert_eq!(section5.template, 3);
+        let DataRepresentation::ComplexPacking(packing) = &section5.data_representation else {
+            panic!("the data representation is not a complex packing");
+        };
+        assert_eq!(packing.missing_value_management, 1);
+        assert_eq!(packing.number_of_groups, 1);
+        assert_eq!(packing.last_group_length, 6);
+        let differencing = packing.spatial_differencing.as_ref().unwrap();
+        assert_eq!(differencing.order, 1);
+        assert_eq!(differencing.number_of_octets, 2);
+        let expected = "\
+118.006250,47.995833,25.0
+118.018750,47.995833,25.1
+118.006250,47.987500,25.3
+118.018750,47.987500,25.5
+118.031250,47.987500,25.7
+";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn can_convert_with_bitmap() {
+        // 1データのビット数4
+        let section5 = simple_packing_section5(4, 4);
+        // ビットマップ: 0b1011_0100（最後の2ビットは詰め物）
+        // 圧縮された値: 1, 2, 3, 4
+        let section7 = [0x12, 0x34];
+        // 同じGRIB2報の2番目の資料に、最初の資料で定義したビットマップを適用
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        let (section1, section4) = (&sample[SAMPLE_SECTION1], &sample[SAMPLE_SECTION4]);
+        let bytes = build_grib2(&[
+            section1,
+            &sample_section3(3, 2),
+            section4,
+            &section(5, &section5),
+            &section(6, &[0, 0xb4]),
+            &section(7, &section7),
+            section4,
+            &section(5, &section5),
+            &section(6, &[254]),
+            &section(7, &section7),
+        ]);
+        let input = temp_path("bitmap.bin");
+        let output = temp_path("bitmap.csv");
+        std::fs::write(&input, bytes).unwrap();
+
+        let grib2 = Grib2Csv::new(&input, false).unwrap();
+        assert_eq!(grib2.number_of_messages(), 2);
+        let indicators: Vec<u8> = grib2
+            .messages()
+            .map(|m| m.metadata().section6.bitmap_indicator)
+            .collect();
+        assert_eq!(indicators, vec![0, 254]);
+        let expected = "\
+118.006250,47.995833,1
+118.031250,47.995833,2
+118.006250,47.987500,3
+118.031250,47.987500,4
+";
+        for index in 0..2 {
+            grib2
+                .convert_message(index, &output, Boundary::default())
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        }
+        std::fs::remove_file(&output).unwrap();
+
+        // 以前に定義されたビットマップが存在しない場合や、別のGRIB2報で定義されている場合はエラー
+        let first = build_message(3, 2, &section5, &[0, 0xb4], &section7);
+        let second = build_message(3, 2, &section5, &[254], &section7);
+        std::fs::write(&input, &second).unwrap();
+        assert!(Grib2Csv::new(&input, false).is_err());
+        std::fs::write(&input, [first, second].concat()).unwrap();
+        assert!(Grib2Csv::new(&input, false).is_err());
+        std::fs::remove_file(input).unwrap();
+    }
+
+    #[test]
+    fn can_convert_lambert_conformal_grid() {
+        // 北緯30度、東経130度から、x方向とy方向に5kmの間隔で並ぶ2x2の格子
+        // （標準緯線30度と60度、中心経線140度、南から北に走査）
+        let section3 = section(
+            3,
+            &[
+                &[0][..],
+                &4u32.to_be_bytes(),
+                &[0, 0],
+                &30u16.to_be_bytes(),
+                &[4],
+                &[0; 15],
+                &2u32.to_be_bytes(),
+                &2u32.to_be_bytes(),
+                &30_000_000u32.to_be_bytes(),
+                &130_000_000u32.to_be_bytes(),
+                &[0],
+                &60_000_000u32.to_be_bytes(),
+                &140_000_000u32.to_be_bytes(),
+                &5_000_000u32.to_be_bytes(),
+                &5_000_000u32.to_be_bytes(),
+                &[0x00, 0x40],
+                &30_000_000u32.to_be_bytes(),
+                &60_000_000u32.to_be_bytes(),
+                &[0; 8],
+            ]
+            .concat(),
+        );
+        // R = 1.0、E = 0、D = 0、1データのビット数8、元の資料値の種類0（浮動小数点）
+        let section5 = [
+            &4u32.to_be_bytes()[..],
+            &0u16.to_be_bytes(),
+            &1f32.to_bits().to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &[8, 0],
+        ]
+        .concat();
+        let section7 = [0, 1, 2, 3];
+        let bytes = build_message_with_section3(&section3, &section5, &[255], &section7);
+
+        let (metadata, csv) = convert_bytes("grib2csv_lambert_conformal", &bytes);
+        let section3 = &metadata.section3;
+        assert_eq!(section3.template, 30);
+        let GridDefinition::LambertConformal(grid) = &section3.grid_definition else {
+            panic!("the grid definition is not a lambert conformal grid");
+        };
+        assert_eq!(grid.lad, 60_000_000);
+        assert_eq!(grid.lov, 140_000_000);
+        assert_eq!(grid.dx, 5_000_000);
+        assert_eq!(grid.latin1, Some(30_000_000));
+        assert_eq!(grid.latin2, Some(60_000_000));
+        let expected = "\
+130.000000,30.000000,1
+130.051421,30.005605,2
+129.993540,30.044758,3
+130.044994,30.050367,4
+";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn can_read_earth_shapes() {
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        // 地球の形状と、地球球体の半径及び地球回転楕円体の軸の長さを置き換えたGRIB2報を読み込む
+        let read = |name: &str, earth_shape: &[u8]| {
+            let mut section3 = sample[SAMPLE_SECTION3].to_vec();
+            section3[SECTION3_EARTH_SHAPE].copy_from_slice(earth_shape);
+            let bytes = build_message_with_section3(
+                &section3,
+                &sample[SAMPLE_SECTION5_BODY],
+                &[255],
+                &sample[SAMPLE_SECTION7_DATA..sample.len() - 4],
+            );
+            let input = temp_path(&format!("{name}.bin"));
+            std::fs::write(&input, bytes).unwrap();
+            let result = Grib2Csv::new(&input, false).map(|grib2| {
+                let shape = &grib2.metadata().section3.earth_shape;
+                (shape.shape, shape.semi_major_axis, shape.semi_minor_axis)
+            });
+            std::fs::remove_file(input).unwrap();
+            result
+        };
+
+        // 作成中枢が半径を6,371,229m（尺度因子1）と指定した球体
+        let bytes = [&[1, 1][..], &63_712_290u32.to_be_bytes(), &[0; 10]].concat();
+        assert_eq!(
+            read("grib2csv_earth_sphere", &bytes).unwrap(),
+            (1, 6_371_229.0, 6_371_229.0)
+        );
+        // WGS84回転楕円体
+        let bytes = [&[5][..], &[0xff; 15]].concat();
+        let (shape, major, minor) = read("grib2csv_earth_wgs84", &bytes).unwrap();
+        assert_eq!((shape, major), (5, 6_378_137.0));
+        assert!((minor - 6_356_752.314_245).abs() < 1e-3);
+        // 作成中枢が長軸と短軸をキロメートル単位で指定した回転楕円体
+        let bytes = [
+            &[3, 0xff][..],
+            &[0xff; 4],
+            &[3],
+            &6_378_137u32.to_be_bytes(),
+            &[4],
+            &63_567_523u32.to_be_bytes(),
+        ]
+        .concat();
+        let (shape, major, minor) = read("grib2csv_earth_spheroid", &bytes).unwrap();
+        assert_eq!(shape, 3);
+        assert!((major - 6_378_137.0).abs() < 1e-6);
+        assert!((minor - 6_356_752.3).abs() < 1e-6);
+        // 半径が指定されていない球体と、符号表3.2にない地球の形状
+        let bytes = [&[1][..], &[0xff; 15]].concat();
+        assert!(read("grib2csv_earth_missing", &bytes).is_err());
+        let bytes = [&[11][..], &[0xff; 15]].concat();
+        assert!(read("grib2csv_earth_unsupported", &bytes).is_err());
+    }
+
+    #[test]
+    fn cell_area_follows_earth_shape() {
+        // GRS80回転楕円体の、北緯39.5度から40.5度、経度1度の領域
+        let section3 = lattice_section3();
+        let area = section3.cell_area(0);
+        assert!((area - 9_481_553_109.402).abs() < 1e-2, "{area}");
+        // 半径6,371,229mの球体の場合
+        let section3 = Section3 {
+            earth_shape: EarthShape::new(
+                EARTH_SHAPE_SPHERE_6371229,
+                Ellipsoid::from_axes(6_371_229.0, 6_371_229.0),
+            ),
+            ..lattice_section3()
+        };
+        let area = section3.cell_area(0);
+        assert!((area - 9_472_172_963.780).abs() < 1e-2, "{area}");
+    }
+
+    #[test]
+    fn projection_centre_flag() {
+        // 1格子点のポーラーステレオ図法の格子を、投影中心フラグを指定して読み込む
+        let read = |name: &str, lad: u32, projection_centre: u8| {
+            let section3 = section(
+                3,
+                &[
+                    &[0][..],
+                    &1u32.to_be_bytes(),
+                    &[0, 0],
+                    &20u16.to_be_bytes(),
+                    &[4],
+                    &[0; 15],
+                    &1u32.to_be_bytes(),
+                    &1u32.to_be_bytes(),
+                    &(0x8000_0000u32 | 70_000_000).to_be_bytes(),
+                    &[0; 5],
+                    &lad.to_be_bytes(),
+                    &[0; 4],
+                    &5_000_000u32.to_be_bytes(),
+                    &5_000_000u32.to_be_bytes(),
+                    &[projection_centre, 0x00],
+                ]
+                .concat(),
+            );
+            let section5 = simple_packing_section5(1, 8);
+            let bytes = build_message_with_section3(&section3, &section5, &[255], &[7]);
+            let input = temp_path(&format!("{name}.bin"));
+            std::fs::write(&input, bytes).unwrap();
+            let result = Grib2Csv::new(&input, false);
+            std::fs::remove_file(input).unwrap();
+            result
+        };
+
+        // 南極が投影面上にあり、南緯60度で縮尺が正しい
+        let grib2 = read("grib2csv_south_pole", 0x8000_0000 | 60_000_000, 0x80).unwrap();
+        let GridDefinition::PolarStereographic(grid) = &grib2.metadata().section3.grid_definition
+        else {
+            panic!("the grid definition is not a polar stereographic grid");
+        };
+        assert_eq!(grid.lad, -60_000_000);
+        assert_eq!(grib2.metadata().section3.coordinate(0), (0, -70_000_000));
+        // 投影中心が双極
+        assert!(read("grib2csv_bipolar", 60_000_000, 0x40).is_err());
+    }
+
+    #[test]
+    fn validation_policy_accepts_test_products_and_any_document_kinds() {
+        // 作成ステータスを現業的試験プロダクト（第1節20オクテット）、資料の種類を予報プロダクト
+        // （第1節21オクテット）に書き換えたファイルを作成
+        let mut sample = std::fs::read(SAMPLE_FILE).unwrap();
+        sample[SAMPLE_SECTION1.start + SECTION1_CREATION_STATUS] = 1;
+        sample[SAMPLE_SECTION1.start + SECTION1_DOCUMENT_KIND] = 1;
+        let input = temp_path("validation_policy.bin");
+        std::fs::write(&input, &sample).unwrap();
+
+        // 既定の検証方針では、読み込みに失敗
+        assert!(Grib2Csv::new(&input, true).is_err());
+        let policy = ValidationPolicyBuilder::default()
+            .accept_test_products(true)
+            .build();
+        assert!(Grib2Csv::with_policy(&input, true, policy).is_err());
+
+        // 作成ステータスと資料の種類を受け入れる検証方針では、読み込みに成功
+        let policy = ValidationPolicyBuilder::default()
+            .accept_test_products(true)
+            .accept_any_document_kinds(true)
+            .build();
+        let grib2 = Grib2Csv::with_policy(&input, true, policy).unwrap();
+        assert_eq!(grib2.metadata().section1.creation_status, 1);
+        assert_eq!(grib2.metadata().section1.document_kind, 1);
+
+        std::fs::remove_file(input).unwrap();
+    }
+
     fn sample_level_values() -> Vec<u16> {
         vec![
             0, 4, 10, 20, 30, 40, 50, 60, 70, 80, 90, 100, 110, 120, 130, 140, 150, 160, 170, 180,
@@ -1033,8 +4274,8 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![3u16];
-        let expected = (3u16, 1u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (3u16, 1u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -1043,8 +4284,8 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![9u16, 12];
-        let expected = (9u16, 2u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (9u16, 2u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -1053,8 +4294,8 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![4u16, 15];
-        let expected = (4u16, 5u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (4u16, 5u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
     }
 
     #[test]
@@ -1063,8 +4304,220 @@ mod tests {
         let maxv = 10;
         let lngu = 2u16.pow(nbit) - 1 - maxv;
         let values = vec![0u16, 13, 12];
-        let expected = (0u16, 8u32);
-        assert_eq!(expected, expand_run_length(&values, maxv, lngu));
+        let expected = (0u16, 8u64);
+        assert_eq!(expected, expand_run_length(&values, maxv, lngu).unwrap());
+    }
+
+    /// ランレングス圧縮オクテット列を展開して、レベル値とそのレベル値が連続する数を関数に渡す。
+    ///
+    /// # 戻り値
+    ///
+    /// 展開した格子点の数。
+    fn decode_run_length<F>(
+        data: &[u8],
+        nbit: u8,
+        maxv: u16,
+        number_of_points: u32,
+        mut f: F,
+    ) -> anyhow::Result<u32>
+    where
+        F: FnMut(u16, u32) -> anyhow::Result<()>,
+    {
+        let mut decoder = RunLengthDecoder::new(data.len() as u64, nbit, maxv, number_of_points)?;
+        let mut bits = BitReader::new(data);
+        while let Some((level, count)) = decoder.next(&mut bits)? {
+            f(level, count)?;
+        }
+
+        Ok(decoder.number_of_read)
+    }
+
+    /// レベル値の列を、資料テンプレート7.200のランレングス符号化でNBITビットごとに符号化する。
+    fn encode_run_length(levels: &[u16], nbit: u8, maxv: u16) -> Vec<u8> {
+        let lngu = ((1u32 << nbit) - 1 - maxv as u32) as u64;
+        // レベル値とランレングス値の列を作成
+        let mut tokens = Vec::new();
+        let mut index = 0;
+        while index < levels.len() {
+            let level = levels[index];
+            let count = levels[index..].iter().take_while(|&&v| v == level).count();
+            tokens.push(level as u32);
+            let mut rl = (count - 1) as u64;
+            while 0 < rl {
+                tokens.push((maxv as u64 + 1 + rl % lngu) as u32);
+                rl /= lngu;
+            }
+            index += count;
+        }
+        // 最上位ビットから詰めて、オクテット列を作成
+        let mut bytes = Vec::new();
+        let (mut buffer, mut bits) = (0u64, 0u32);
+        for token in tokens {
+            buffer = (buffer << nbit) | token as u64;
+            bits += nbit as u32;
+            while 8 <= bits {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+        if 0 < bits {
+            bytes.push((buffer << (8 - bits)) as u8);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn decode_run_length_of_various_bit_widths() {
+        for (nbit, maxv) in [
+            (2u8, 1u16),
+            (4, 10),
+            (6, 40),
+            (8, 84),
+            (12, 1000),
+            (16, 3000),
+        ] {
+            // 欠測値（レベル0）や、長く連続するレベル値を含むレベル値の列を作成
+            let mut levels = Vec::new();
+            for i in 0..200u16 {
+                let level = (i * 7) % (maxv + 1);
+                let count = [1usize, 2, 3, 17, 100, 5000][(i % 6) as usize];
+                levels.extend(std::iter::repeat_n(level, count));
+            }
+            let bytes = encode_run_length(&levels, nbit, maxv);
+
+            let mut decoded = Vec::new();
+            let number_of_read =
+                decode_run_length(&bytes, nbit, maxv, levels.len() as u32, |level, count| {
+                    decoded.extend(std::iter::repeat_n(level, count as usize));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(number_of_read as usize, levels.len(), "nbit={nbit}");
+            assert_eq!(decoded, levels, "nbit={nbit}");
+        }
+    }
+
+    #[test]
+    fn read_section5_rejects_too_short_length() {
+        let sample = std::fs::read(SAMPLE_FILE).unwrap();
+        let mut section5 =
+            sample[SAMPLE_SECTION5_BODY.start - 5..SAMPLE_SECTION5_BODY.end].to_vec();
+        assert!(read_section5(&mut Cursor::new(&section5)).is_ok());
+        // 節の長さを、ランレングス圧縮の資料表現テンプレートより短い長さに書き換え
+        section5[..4].copy_from_slice(&10u32.to_be_bytes());
+        assert!(read_section5(&mut Cursor::new(&section5)).is_err());
+    }
+
+    #[test]
+    fn decode_run_length_rejects_invalid_bit_widths() {
+        let bytes = [0u8; 4];
+        for (nbit, maxv) in [(0u8, 0u16), (17, 10), (4, 15)] {
+            let result = decode_run_length(&bytes, nbit, maxv, 1, |_, _| Ok(()));
+            assert!(result.is_err(), "nbit={nbit}, maxv={maxv}");
+        }
+    }
+
+    #[test]
+    fn decode_run_length_rejects_too_long_runs() {
+        // NBIT = 4、MAXV = 10（LNGU = 5）で、レベル値3が5連続するセット{3, 15}
+        let bytes = [0x3f];
+        assert_eq!(
+            decode_run_length(&bytes, 4, 10, 5, |_, _| Ok(())).unwrap(),
+            5
+        );
+        // ランレングスが残りの資料点数を超える
+        assert!(decode_run_length(&bytes, 4, 10, 4, |_, _| Ok(())).is_err());
+        let bytes = [0x13, 0x3f];
+        assert!(decode_run_length(&bytes, 4, 10, 5, |_, _| Ok(())).is_err());
+        // ランレングスが64ビットを超える
+        let values: Vec<u16> = [3].into_iter().chain([15; 32]).collect();
+        assert!(expand_run_length(&values, 10, 5).is_err());
+        assert!(expand_run_length(&[3, 15, 11, 11, 11], 10, 5).is_ok());
+    }
+
+    #[test]
+    fn decode_run_length_rejects_sets_without_level_values() {
+        // NBIT = 4、MAXV = 10で、最初の値がランレングス値15
+        let bytes = [0xf3];
+        assert!(decode_run_length(&bytes, 4, 10, 5, |_, _| Ok(())).is_err());
+        assert!(expand_run_length(&[15, 12], 10, 5).is_err());
+        // 第7節の最初のオクテットを、今回の圧縮に用いたレベルの最大値を超える値に書き換え
+        let mut bytes = std::fs::read(SAMPLE_FILE).unwrap();
+        let offset = Grib2Csv::new(SAMPLE_FILE, false).unwrap().messages[0].section7_offset;
+        bytes[offset as usize + 5] = 0xff;
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        assert!(grib2.grid().is_err());
+    }
+
+    #[test]
+    fn read_section7_rejects_length_past_the_message() {
+        let grib2 = Grib2Csv::new(SAMPLE_FILE, false).unwrap();
+        let message = &grib2.messages[0];
+        let mut bytes = std::fs::read(SAMPLE_FILE).unwrap();
+        let offset = message.section7_offset as usize;
+        assert!(message.read_section7(&mut Cursor::new(&bytes[..])).is_ok());
+        // 第7節の長さを、GRIB2報の末尾を超える長さに書き換え
+        bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = message
+            .read_section7(&mut Cursor::new(&bytes[..]))
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds the end of the message"));
+        // 入力がGRIB2報の途中で終わっている
+        let bytes = std::fs::read(SAMPLE_FILE).unwrap();
+        let truncated = &bytes[..bytes.len() - 8];
+        assert!(message.read_section7(&mut Cursor::new(truncated)).is_err());
+    }
+
+    #[test]
+    fn output_values_rejects_points_past_the_grid() {
+        let grib2 = Grib2Csv::new(SAMPLE_FILE, false).unwrap();
+        let message = &grib2.messages[0];
+        let number_of_points = message.metadata.section3.number_of_points;
+        let output = temp_path("points_past_the_grid.csv");
+        let mut writer = BufWriter::new(File::create(&output).unwrap());
+        let boundary = Boundary::default();
+        // 最後の格子点を超える格子点を含む物理値は、1行も書き込まない
+        let mut index = number_of_points - 1;
+        assert!(message
+            .output_values(&mut writer, Some(1.0), 2, &mut index, &boundary, None)
+            .is_err());
+        assert_eq!(index, number_of_points - 1);
+        // 最後の格子点までの物理値は書き込む
+        message
+            .output_values(&mut writer, Some(1.0), 1, &mut index, &boundary, None)
+            .unwrap();
+        assert_eq!(index, number_of_points);
+        writer.flush().unwrap();
+        let lines = std::fs::read_to_string(&output).unwrap().lines().count();
+        assert_eq!(lines, 1);
+    }
+
+    #[test]
+    fn representative_value_applies_data_value_factor() {
+        let mut section5 = RunLength {
+            bits_per_data: 8,
+            max_level_at_file: 3,
+            max_level: 3,
+            data_value_factor: 1,
+            level_values: vec![0, 4, 2550],
+        };
+        let expected = [None, Some(0.0), Some(0.4), Some(255.0), None];
+        for (level, expected) in expected.iter().enumerate() {
+            assert_eq!(section5.representative_value(level as u16), *expected);
+        }
+        assert_eq!(section5.decimal_places(), 1);
+
+        // 土壌雨量指数などの尺度因子が異なるプロダクト
+        section5.data_value_factor = 2;
+        assert_eq!(section5.representative_value(3), Some(25.5));
+        assert_eq!(section5.decimal_places(), 2);
+        section5.data_value_factor = 0;
+        assert_eq!(section5.representative_value(3), Some(2550.0));
+        assert_eq!(section5.decimal_places(), 0);
+        section5.data_value_factor = -1;
+        assert_eq!(section5.representative_value(2), Some(40.0));
+        assert_eq!(section5.decimal_places(), 0);
     }
 
     #[test]
@@ -1074,6 +4527,7 @@ mod tests {
             southernmost: Some(35000000),
             westernmost: Some(135000000),
             easternmost: Some(136000000),
+            ..Default::default()
         };
         let coordinates = vec![
             (135000000, 36000000),
@@ -1094,6 +4548,7 @@ mod tests {
             southernmost: Some(35000000),
             westernmost: Some(135000000),
             easternmost: Some(136000000),
+            ..Default::default()
         };
         let coordinates = vec![
             (134900000, 36000000),
@@ -1106,23 +4561,436 @@ mod tests {
         }
     }
 
+    #[test]
+    fn boundary_with_mesh_codes() {
+        // 東経139度以東の、2次メッシュ533946と3次メッシュ53394519
+        let boundary = BoundaryBuilder::default()
+            .westernmost(Some(139000000))
+            .mesh_codes(Some(MeshCodes::parse("533946,53394519").unwrap()))
+            .build();
+        assert!(boundary.contains(139767000, 35681000));
+        assert!(boundary.contains(139743750, 35679167));
+        assert!(!boundary.contains(139612500, 35745833));
+        // 東経139度以西の3次メッシュ
+        let codes = MeshCodes::parse("53387797").unwrap();
+        assert!(codes.contains(138968750, 35995833));
+        let boundary = BoundaryBuilder::default()
+            .westernmost(Some(139000000))
+            .mesh_codes(Some(codes))
+            .build();
+        assert!(!boundary.contains(138968750, 35995833));
+    }
+
+    #[test]
+    fn boundary_wraps_around_antimeridian() {
+        // 東経170度から西経170度、南緯10度から南緯20度
+        let boundary = Boundary {
+            northernmost: Some(-10000000),
+            southernmost: Some(-20000000),
+            westernmost: Some(170000000),
+            easternmost: Some(-170000000),
+            ..Default::default()
+        };
+        for dataset in [
+            (175000000, -15000000),
+            (180000000, -10000000),
+            (-175000000, -20000000),
+        ] {
+            assert!(boundary.contains(dataset.0, dataset.1), "{:?}", dataset);
+        }
+        for dataset in [
+            (165000000, -15000000),
+            (-165000000, -15000000),
+            (175000000, -9000000),
+        ] {
+            assert!(!boundary.contains(dataset.0, dataset.1), "{:?}", dataset);
+        }
+
+        // 0度から360度で表現した経度も、正規化して比較する
+        let boundary = Boundary {
+            westernmost: Some(170000000),
+            easternmost: Some(190000000),
+            ..Default::default()
+        };
+        assert!(boundary.contains(-175000000, 0));
+        assert!(!boundary.contains(-165000000, 0));
+        // 1周以上の範囲はすべての経度を含む
+        let boundary = Boundary {
+            westernmost: Some(-180000000),
+            easternmost: Some(180000000),
+            ..Default::default()
+        };
+        assert!(boundary.contains(0, 0));
+    }
+
+    #[test]
+    fn can_convert_grid_across_antimeridian_in_southern_hemisphere() {
+        // 南緯10度、東経179.9875度から始まる3x2の格子
+        let section3 = sample_section3_from(3, 2, -10_000_000, 179_987_500);
+        let section5 = simple_packing_section5(6, 8);
+        let section7 = [1, 2, 3, 4, 5, 6];
+        let bytes = build_message_with_section3(&section3, &section5, &[255], &section7);
+
+        let (metadata, csv) = convert_bytes("grib2csv_antimeridian", &bytes);
+        assert_eq!(metadata.section3.first_latitude, -10_000_000);
+        assert_eq!(metadata.section3.first_longitude, 179_987_500);
+        let expected = "\
+179.987500,-10.000000,1
+180.000000,-10.000000,2
+-179.987500,-10.000000,3
+179.987500,-10.008333,4
+180.000000,-10.008333,5
+-179.987500,-10.008333,6
+";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn can_convert_points_in_area() {
+        let section5 = simple_packing_section5(6, 8);
+        let section7 = [1, 2, 3, 4, 5, 6];
+        let bytes = build_message(3, 2, &section5, &[255], &section7);
+        let grib2 = Grib2Csv::from_bytes(&bytes, false, ValidationPolicy::default()).unwrap();
+        // 2番目の格子点（118.01875度、47.995833度）のみを含み、5番目の格子点を中心とする格子と交わる矩形
+        let area = Area::parse(
+            "POLYGON ((118.015 47.99, 118.022 47.99, 118.022 48.0, 118.015 48.0, 118.015 47.99))",
+        )
+        .unwrap();
+        let convert = |mode: AreaMode| {
+            let output = temp_path("area.csv");
+            let boundary = BoundaryBuilder::default()
+                .area(Some(area.clone()))
+                .area_mode(mode)
+                .build();
+            grib2.convert(&output, boundary).unwrap();
+            let csv = std::fs::read_to_string(&output).unwrap();
+            std::fs::remove_file(output).unwrap();
+            csv
+        };
+
+        assert_eq!(convert(AreaMode::CellCenter), "118.018750,47.995833,2\n");
+        assert_eq!(
+            convert(AreaMode::CellIntersects),
+            "118.018750,47.995833,2\n118.018750,47.987500,5\n"
+        );
+    }
+
+    #[test]
+    fn can_convert_zonal_statistics() {
+        let section5 = simple_packing_section5(6, 8);
+        let section7 = [0, 2, 3, 4, 5, 6];
+        let bytes = build_message(3, 2, &section5, &[255], &section7);
+        let grib2 = Grib2Csv::from_bytes(&bytes, true, ValidationPolicy::default()).unwrap();
+        // 1番目の領域は北側の行の1番目と2番目の格子と一致し、2番目の領域は南側の行の1番目と2番目の格子点を含み、
+        // 北側の行の格子の約28%と、東側の列の格子の40%と重なる
+        let zones = Zone::parse(
+            r#"{
+                "type": "FeatureCollection",
+                "features": [
+                    {
+                        "type": "Feature",
+                        "properties": {"name": "a,1"},
+                        "geometry": {
+                            "type": "Polygon",
+                            "coordinates": [[[118.0, 47.9917], [118.025, 47.9917], [118.025, 48.0], [118.0, 48.0], [118.0, 47.9917]]]
+                        }
+                    },
+                    {
+                        "type": "Feature",
+                        "properties": {"name": "b"},
+                        "geometry": {
+                            "type": "Polygon",
+                            "coordinates": [[[118.0, 47.9], [118.03, 47.9], [118.03, 47.994], [118.0, 47.994], [118.0, 47.9]]]
+                        }
+                    },
+                    {
+                        "type": "Feature",
+                        "properties": {"name": "empty"},
+                        "geometry": {
+                            "type": "Polygon",
+                            "coordinates": [[[130.0, 30.0], [131.0, 30.0], [131.0, 31.0], [130.0, 30.0]]]
+                        }
+                    }
+                ]
+            }"#,
+            "name",
+        )
+        .unwrap();
+
+        // 格子点が領域に含まれる格子点の統計量
+        let output = temp_path("zonal_statistics.csv");
+        grib2
+            .convert_zonal_statistics(&output, &zones, AreaMode::CellCenter)
+            .unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(output).unwrap();
+        assert_eq!(
+            csv,
+            "id,cells,mean,min,max,weighted_mean,rainy_coverage\n\
+             \"a,1\",2,1.000000,0,2,1.000000,0.500000\n\
+             b,2,4.500000,4,5,4.500000,1.000000\n\
+             empty,0,,,,,\n"
+        );
+
+        // 格子が領域と交わる格子点の統計量は、領域と重なる面積で重み付けする
+        let statistics = grib2
+            .zonal_statistics(&zones, AreaMode::CellIntersects)
+            .unwrap();
+        assert_eq!(statistics[0].cells, 2);
+        assert_eq!(statistics[0].weighted_mean, Some(1.0));
+        let zone = &statistics[1];
+        assert_eq!(zone.cells, 6);
+        assert_eq!(zone.mean, Some(20.0 / 6.0));
+        assert_eq!((zone.min, zone.max), (Some(0.0), Some(6.0)));
+        // 重み: 北側の行は0.28、0.28、0.112、南側の行は1、1、0.4
+        let weighted_mean = zone.weighted_mean.unwrap();
+        assert!(
+            (weighted_mean - 12.296 / 3.072).abs() < 1e-3,
+            "{weighted_mean}"
+        );
+        let rainy_coverage = zone.rainy_coverage.unwrap();
+        assert!(
+            (rainy_coverage - 2.792 / 3.072).abs() < 1e-3,
+            "{rainy_coverage}"
+        );
+        assert_eq!(statistics[2].cells, 0);
+        assert_eq!(statistics[2].mean, None);
+    }
+
+    #[test]
+    fn can_convert_with_mesh_code() {
+        // 東京駅を含む3次メッシュ（53394611）の中心から始まる3x2の格子
+        let section3 = sample_section3_from(3, 2, 35_679_167, 139_768_750);
+        let section5 = simple_packing_section5(6, 8);
+        let section7 = [1, 2, 3, 4, 5, 6];
+        let bytes = build_message_with_section3(&section3, &section5, &[255], &section7);
+
+        let output = temp_path("mesh_code.csv");
+        let grib2 = Grib2Csv::from_bytes(&bytes, true, ValidationPolicy::default())
+            .unwrap()
+            .with_mesh_level(Some(MeshLevel::Third));
+        grib2.convert(&output, Boundary::default()).unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(output).unwrap();
+        let expected = "\
+longitude,latitude,value,mesh_code
+139.768750,35.679167,1,53394611
+139.781250,35.679167,2,53394612
+139.793750,35.679167,3,53394613
+139.768750,35.670834,4,53394601
+139.781250,35.670834,5,53394602
+139.793750,35.670834,6,53394603
+";
+        assert_eq!(csv, expected);
+    }
+
+    /// 経線方向の増加量1度、緯線方向の増加量1度、最西端130度、最東端150度、最北端40度の格子
+    fn lattice_section3() -> Section3 {
+        Section3 {
+            number_of_points: 21 * 11,
+            number_of_points_along_parallel: 21,
+            number_of_points_along_meridian: 11,
+            template: 0,
+            earth_shape: EarthShape::new(EARTH_SHAPE_GRS80, Ellipsoid::grs80()),
+            first_latitude: 40000000,
+            first_longitude: 130000000,
+            scanning_mode: 0,
+            grid_definition: GridDefinition::LatLon(LatLonGrid::new(
+                (130000000, 40000000),
+                (150000000, 30000000),
+                (1000000, 1000000),
+                (21, 11),
+                0,
+            )),
+        }
+    }
+
+    #[test]
+    fn coordinate_follows_scanning_mode() {
+        // 経度130度から132度、緯度38度から40度の3x3の格子で、各格子点の経度と緯度（度）を、
+        // 資料の並び順に並べる
+        let coordinates = |scanning_mode: u8, first_longitude: i32, first_latitude: i32| {
+            let section3 = Section3 {
+                number_of_points: 9,
+                number_of_points_along_parallel: 3,
+                number_of_points_along_meridian: 3,
+                template: 0,
+                earth_shape: EarthShape::new(EARTH_SHAPE_GRS80, Ellipsoid::grs80()),
+                first_latitude: first_latitude * 1000000,
+                first_longitude: first_longitude * 1000000,
+                scanning_mode,
+                grid_definition: GridDefinition::LatLon(LatLonGrid::new(
+                    (first_longitude * 1000000, first_latitude * 1000000),
+                    (0, 0),
+                    (1000000, 1000000),
+                    (3, 3),
+                    scanning_mode,
+                )),
+            };
+            (0..9)
+                .map(|index| {
+                    let (longitude, latitude) = section3.coordinate(index);
+                    (longitude / 1000000, latitude / 1000000)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 西から東、北から南
+        assert_eq!(
+            coordinates(0x00, 130, 40),
+            [
+                (130, 40),
+                (131, 40),
+                (132, 40),
+                (130, 39),
+                (131, 39),
+                (132, 39),
+                (130, 38),
+                (131, 38),
+                (132, 38)
+            ]
+        );
+        // 東から西、北から南
+        assert_eq!(
+            coordinates(0x80, 132, 40),
+            [
+                (132, 40),
+                (131, 40),
+                (130, 40),
+                (132, 39),
+                (131, 39),
+                (130, 39),
+                (132, 38),
+                (131, 38),
+                (130, 38)
+            ]
+        );
+        // 西から東、南から北
+        assert_eq!(
+            coordinates(0x40, 130, 38),
+            [
+                (130, 38),
+                (131, 38),
+                (132, 38),
+                (130, 39),
+                (131, 39),
+                (132, 39),
+                (130, 40),
+                (131, 40),
+                (132, 40)
+            ]
+        );
+        // j方向に連続、北から南、西から東
+        assert_eq!(
+            coordinates(0x20, 130, 40),
+            [
+                (130, 40),
+                (130, 39),
+                (130, 38),
+                (131, 40),
+                (131, 39),
+                (131, 38),
+                (132, 40),
+                (132, 39),
+                (132, 38)
+            ]
+        );
+        // 隣接する行を逆方向に走査
+        assert_eq!(
+            coordinates(0x10, 130, 40),
+            [
+                (130, 40),
+                (131, 40),
+                (132, 40),
+                (132, 39),
+                (131, 39),
+                (130, 39),
+                (130, 38),
+                (131, 38),
+                (132, 38)
+            ]
+        );
+        // j方向に連続、南から北、隣接する列を逆方向に走査
+        assert_eq!(
+            coordinates(0x70, 130, 38),
+            [
+                (130, 38),
+                (130, 39),
+                (130, 40),
+                (131, 40),
+                (131, 39),
+                (131, 38),
+                (132, 38),
+                (132, 39),
+                (132, 40)
+            ]
+        );
+    }
+
+    #[test]
+    fn coordinate_is_not_affected_by_rounded_increment() {
+        // サンプルファイルと同じ、経度方向1/80度、緯度方向1/120度間隔の格子
+        let section3 = Section3 {
+            number_of_points: 2560 * 3360,
+            number_of_points_along_parallel: 2560,
+            number_of_points_along_meridian: 3360,
+            template: 0,
+            earth_shape: EarthShape::new(EARTH_SHAPE_GRS80, Ellipsoid::grs80()),
+            first_latitude: 47995833,
+            first_longitude: 118006250,
+            scanning_mode: 0,
+            grid_definition: GridDefinition::LatLon(LatLonGrid::new(
+                (118006250, 47995833),
+                (149993750, 20004167),
+                (12500, 8333),
+                (2560, 3360),
+                0,
+            )),
+        };
+        // 増分8333を累積すると、最後の行は20.004286度になる
+        assert_eq!(section3.point_coordinate(0, 0), (118006250, 47995833));
+        assert_eq!(section3.point_coordinate(1, 1), (118018750, 47987500));
+        assert_eq!(section3.point_coordinate(0, 1678), (118006250, 34012500));
+        assert_eq!(section3.point_coordinate(0, 3357), (118006250, 20020833));
+        assert_eq!(section3.point_coordinate(2559, 3359), (149993750, 20004167));
+        assert_eq!(
+            section3.nearest_grid_index(149993750, 20004167),
+            Some((2559, 3359))
+        );
+
+        // 最後の格子点が増分と整合しない場合は、増分から座標を求める
+        let mut section3 = lattice_section3();
+        section3.grid_definition = GridDefinition::LatLon(LatLonGrid::new(
+            (130000000, 40000000),
+            (0, 0),
+            (1000000, 1000000),
+            (21, 11),
+            0,
+        ));
+        assert_eq!(section3.point_coordinate(20, 10), (150000000, 30000000));
+        // 格子点の間隔が1/k度でない場合は、最初と最後の格子点の間を等分する
+        section3.grid_definition = GridDefinition::LatLon(LatLonGrid::new(
+            (130000000, 40000000),
+            (150000010, 30000007),
+            (1000000, 999999),
+            (21, 11),
+            0,
+        ));
+        assert_eq!(section3.point_coordinate(10, 5), (140000005, 35000004));
+        assert_eq!(section3.point_coordinate(20, 10), (150000010, 30000007));
+    }
+
     #[test]
     fn move_lattice_for_missing_value1() {
         // 現在の緯度と経度が135度、40度で、レベル0が10個連続したとする。
         // 経線方向の増加量1度、緯線方向の増加量1度
         // 最西端130度、最東端150度
         // 移動後の格子の座標は145度、40度
-        let expected = (145000000u32, 40000000u32);
-        let lattice = move_lattice_for_missing_values(
-            135000000u32,
-            40000000u32,
-            10,
-            1000000,
-            1000000,
-            130000000,
-            150000000,
-        );
-        assert_eq!(lattice, expected);
+        let section3 = lattice_section3();
+        let index = 5;
+        assert_eq!(section3.coordinate(index), (135000000, 40000000));
+        let expected = (145000000, 40000000);
+        assert_eq!(section3.coordinate(index + 10), expected);
     }
 
     #[test]
@@ -1131,17 +4999,11 @@ mod tests {
         // 経線方向の増加量1度、緯線方向の増加量1度
         // 最西端130度、最東端150度
         // 移動後の格子の座標は150度、40度
-        let expected = (150000000u32, 40000000u32);
-        let lattice = move_lattice_for_missing_values(
-            140000000u32,
-            40000000u32,
-            10u32,
-            1000000u32,
-            1000000u32,
-            130000000u32,
-            150000000u32,
-        );
-        assert_eq!(lattice, expected);
+        let section3 = lattice_section3();
+        let index = 10;
+        assert_eq!(section3.coordinate(index), (140000000, 40000000));
+        let expected = (150000000, 40000000);
+        assert_eq!(section3.coordinate(index + 10), expected);
     }
 
     #[test]
@@ -1150,17 +5012,11 @@ mod tests {
         // 経線方向の増加量1度、緯線方向の増加量1度
         // 最西端130度、最東端150度
         // 移動後の格子の座標は130度、39度
-        let expected = (130000000u32, 39000000u32);
-        let lattice = move_lattice_for_missing_values(
-            140000000u32,
-            40000000u32,
-            11u32,
-            1000000u32,
-            1000000u32,
-            130000000u32,
-            150000000u32,
-        );
-        assert_eq!(lattice, expected);
+        let section3 = lattice_section3();
+        let index = 10;
+        assert_eq!(section3.coordinate(index), (140000000, 40000000));
+        let expected = (130000000, 39000000);
+        assert_eq!(section3.coordinate(index + 11), expected);
     }
 
     #[test]
@@ -1168,17 +5024,11 @@ mod tests {
         // 現在の緯度と経度が145度、40度で、レベル0が50個連続したとする。
         // 経線方向の増加量1度、緯線方向の増加量1度
         // 最西端130度、最東端150度
-        // 移動後の格子の座標は134度、37度
-        let expected = (132000000u32, 37000000u32);
-        let lattice = move_lattice_for_missing_values(
-            145000000u32,
-            40000000u32,
-            50u32,
-            1000000u32,
-            1000000u32,
-            130000000u32,
-            150000000u32,
-        );
-        assert_eq!(lattice, expected);
+        // 移動後の格子の座標は132度、37度
+        let section3 = lattice_section3();
+        let index = 15;
+        assert_eq!(section3.coordinate(index), (145000000, 40000000));
+        let expected = (132000000, 37000000);
+        assert_eq!(section3.coordinate(index + 50), expected);
     }
 }
@@ -1,5 +1,13 @@
-use clap::Parser;
-use grib2csv::{BoundaryBuilder, Grib2Csv};
+use std::collections::HashSet;
+use std::io::{BufRead, Read, Seek};
+use std::path::{Path, PathBuf};
+
+use clap::{ArgGroup, Parser};
+use grib2csv::{
+    Area, AreaMode, Boundary, BoundaryBuilder, Grib2Archive, Grib2Csv, InputReader, MeshCodes,
+    MeshLevel, ValidationPolicyBuilder, Zone,
+};
+use time::PrimitiveDateTime;
 
 /// コマンドライン引数
 #[derive(Parser)]
@@ -11,26 +19,107 @@ use grib2csv::{BoundaryBuilder, Grib2Csv};
         欠測値を持つ格子点は、CSVファイルに出力されません。\n\
         格子点を出力する領域を指定する場合、度単位の緯度または経度を1,000,000倍した整数部を指定してください。"
 )]
+#[clap(group(ArgGroup::new("regions").args(["area", "zones"])))]
 struct Args {
     /// 入力GRIB2ファイル
-    #[arg(help = "入力GRIB2ファイルのパス")]
+    #[arg(help = "入力GRIB2ファイルのパス(-を指定した場合は標準入力)")]
     input: String,
 
     /// CSVファイルに出力する格子点の最北端の緯度
-    #[arg(short, long, help = "格子点を出力する最北端の緯度(例:36000000)")]
-    northernmost: Option<u32>,
+    #[arg(
+        short,
+        long,
+        allow_negative_numbers = true,
+        help = "格子点を出力する最北端の緯度(例:36000000、南緯は負の値)"
+    )]
+    northernmost: Option<i32>,
 
     /// CSVファイルに出力する格子点の最南端の緯度
-    #[arg(short, long, help = "格子点を出力する最南端の緯度(例:35000000)")]
-    southernmost: Option<u32>,
+    #[arg(
+        short,
+        long,
+        allow_negative_numbers = true,
+        help = "格子点を出力する最南端の緯度(例:35000000、南緯は負の値)"
+    )]
+    southernmost: Option<i32>,
 
     /// CSVファイルに出力する格子点の最西端の経度
-    #[arg(short, long, help = "格子点を出力する最西端の経度(例:135000000)")]
-    westernmost: Option<u32>,
+    #[arg(
+        short,
+        long,
+        allow_negative_numbers = true,
+        help = "格子点を出力する最西端の経度(例:135000000、西経は負の値)"
+    )]
+    westernmost: Option<i32>,
 
     /// CSVファイルに出力する格子点の最西端の経度
-    #[arg(short, long, help = "格子点を出力する最東端の経度(例:136000000)")]
-    easternmost: Option<u32>,
+    #[arg(
+        short,
+        long,
+        allow_negative_numbers = true,
+        help = "格子点を出力する最東端の経度(例:136000000、西経は負の値)"
+    )]
+    easternmost: Option<i32>,
+
+    /// CSVファイルに出力する格子点を含む地域メッシュ・コード
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "格子点を出力する地域メッシュ・コード(1次、2次及び3次メッシュ・コードを混在可能、カンマ区切り、例:5339,53394611)"
+    )]
+    mesh_codes: Vec<String>,
+
+    /// CSVファイルに出力する格子点を含む地域メッシュ・コードを記録したファイル
+    #[arg(
+        long,
+        help = "格子点を出力する地域メッシュ・コードを、カンマ、空白または改行で区切って記録したファイルのパス"
+    )]
+    mesh_codes_file: Option<String>,
+
+    /// CSVファイルに出力する格子点を含む領域を、GeoJSONまたはWKT形式で記録したファイル
+    #[arg(
+        long,
+        help = "格子点を出力する領域(ポリゴンまたはマルチポリゴン)を、GeoJSONまたはWKT形式で記録したファイルのパス"
+    )]
+    area: Option<String>,
+
+    /// 格子点を領域に含める条件
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = parse_area_mode,
+        default_value = "center",
+        requires = "regions",
+        help = "--areaまたは--zonesで、格子点を領域に含める条件(center:格子点が領域に含まれる、intersects:格子点を中心とする格子が領域と交わる)"
+    )]
+    area_mode: AreaMode,
+
+    /// 物理値の統計量を集計する領域を記録した、GeoJSON形式のフィーチャー・コレクションのファイル
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "northernmost",
+            "southernmost",
+            "westernmost",
+            "easternmost",
+            "mesh_codes",
+            "mesh_codes_file",
+            "area",
+            "mesh_code",
+        ],
+        help = "格子点の代わりに、フィーチャーごとの物理値の統計量(平均、最小値、最大値、面積で重み付けした平均、0より大きい格子の面積の比率)を出力する、GeoJSON形式のフィーチャー・コレクションのファイルのパス"
+    )]
+    zones: Option<String>,
+
+    /// 領域の識別子を記録したフィーチャーのプロパティ
+    #[arg(
+        long,
+        value_name = "PROPERTY",
+        default_value = "id",
+        requires = "zones",
+        help = "領域の識別子として出力するフィーチャーのプロパティの名前"
+    )]
+    zone_id: String,
 
     /// CSVファイルにヘッダを出力しないかを示すフラグ
     #[arg(
@@ -40,19 +129,298 @@ struct Args {
     )]
     no_header: bool,
 
+    /// CSVファイルに出力する地域メッシュ・コードの区画の種類
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        value_parser = parse_mesh_level,
+        help = "格子点を含む地域メッシュ・コードを出力する区画の種類(1:1次メッシュ、2:2次メッシュ、3:3次メッシュ)"
+    )]
+    mesh_code: Option<MeshLevel>,
+
+    /// CSVファイルに変換するGRIB2報の番号
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        conflicts_with = "all_messages",
+        help = "変換するGRIB2報の番号(1から開始、カンマ区切りで複数指定可能、例:1,3)"
+    )]
+    messages: Vec<usize>,
+
+    /// すべてのGRIB2報をCSVファイルに変換するかを示すフラグ
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "ファイルに記録されているすべてのGRIB2報を変換する"
+    )]
+    all_messages: bool,
+
+    /// 試験データなど、現業プロダクト以外の作成ステータスを受け入れるかを示すフラグ
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "現業プロダクト以外の作成ステータス（試験データなど）のGRIB2報を変換する"
+    )]
+    accept_test_products: bool,
+
+    /// 予報プロダクトなど、解析プロダクト以外の資料の種類を受け入れるかを示すフラグ
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "解析プロダクト以外の資料の種類（予報プロダクトなど）のGRIB2報を変換する"
+    )]
+    accept_any_document_kinds: bool,
+
+    /// tarアーカイブに格納されているGRIB2ファイルを一覧表示するかを示すフラグ
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["member", "all_members"],
+        help = "tarアーカイブに格納されているGRIB2ファイルのパスと参照時刻を一覧表示する"
+    )]
+    list_members: bool,
+
+    /// CSVファイルに変換する、tarアーカイブに格納されているGRIB2ファイルのパス
+    #[arg(
+        long,
+        conflicts_with = "all_members",
+        help = "変換するtarアーカイブ内のGRIB2ファイルのパス"
+    )]
+    member: Option<String>,
+
+    /// tarアーカイブに格納されているすべてのGRIB2ファイルをCSVファイルに変換するかを示すフラグ
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "tarアーカイブに格納されているすべてのGRIB2ファイルを、参照時刻を付与したCSVファイルに変換する"
+    )]
+    all_members: bool,
+
     /// 出力CSVファイル
-    #[arg(help = "出力CSVファイルのパス")]
-    output: String,
+    #[arg(
+        required_unless_present = "list_members",
+        help = "出力CSVファイルのパス"
+    )]
+    output: Option<String>,
 }
 
 fn main() {
     let args = Args::parse();
-    let converter = Grib2Csv::new(args.input, !args.no_header).unwrap();
+    let policy = ValidationPolicyBuilder::default()
+        .accept_test_products(args.accept_test_products)
+        .accept_any_document_kinds(args.accept_any_document_kinds)
+        .build();
     let boundary = BoundaryBuilder::default()
         .northernmost(args.northernmost)
         .southernmost(args.southernmost)
         .westernmost(args.westernmost)
         .easternmost(args.easternmost)
+        .mesh_codes(mesh_codes(&args))
+        .area(
+            args.area
+                .as_ref()
+                .map(|path| Area::from_file(path).unwrap()),
+        )
+        .area_mode(args.area_mode)
         .build();
-    converter.convert(args.output, boundary).unwrap();
+    let zones = args
+        .zones
+        .as_ref()
+        .map(|path| Zone::from_file(path, &args.zone_id).unwrap());
+    let zones = zones.as_deref();
+    if args.list_members || args.member.is_some() || args.all_members {
+        // tarアーカイブに格納されているGRIB2ファイルを変換
+        let archive = Grib2Archive::with_policy(&args.input, !args.no_header, policy);
+        if args.list_members {
+            for member in archive.members().unwrap() {
+                println!(
+                    "{}\t{}\t{}",
+                    member.name,
+                    reference_time_label(member.referenced_at),
+                    member.number_of_messages
+                );
+            }
+        } else if let Some(name) = &args.member {
+            let converter = archive
+                .member(name)
+                .unwrap()
+                .with_mesh_level(args.mesh_code);
+            convert(&converter, &args, output_path(&args), boundary, zones);
+        } else {
+            // 参照時刻が同じGRIB2ファイルを、同じCSVファイルに上書きしないように、
+            // CSVファイルを書き込む前に、GRIB2ファイルの出力先が他のGRIB2ファイルと重複していないことを確認
+            let mut outputs = HashSet::new();
+            archive
+                .for_each(|member, converter| {
+                    let output = member_output_path(output_path(&args), member.referenced_at);
+                    if !outputs.insert(output.clone()) {
+                        panic!(
+                            "the reference time of {} is the same as another member ({})",
+                            member.name,
+                            output.display()
+                        );
+                    }
+                    let converter = converter.with_mesh_level(args.mesh_code);
+                    convert(&converter, &args, &output, boundary.clone(), zones);
+                    Ok(true)
+                })
+                .unwrap();
+        }
+    } else if args.input == "-" {
+        // 標準入力はシークできないため、すべて読み込んで、圧縮されている場合は展開してから変換
+        let mut bytes = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut bytes).unwrap();
+        let reader = InputReader::from_bytes(bytes).unwrap();
+        let converter = Grib2Csv::from_reader(reader, !args.no_header, policy)
+            .unwrap()
+            .with_mesh_level(args.mesh_code);
+        convert(&converter, &args, output_path(&args), boundary, zones);
+    } else {
+        let converter = Grib2Csv::with_policy(&args.input, !args.no_header, policy)
+            .unwrap()
+            .with_mesh_level(args.mesh_code);
+        convert(&converter, &args, output_path(&args), boundary, zones);
+    }
+}
+
+/// コマンドライン引数で指定された地域メッシュの区画の種類を解析する。
+fn parse_mesh_level(value: &str) -> Result<MeshLevel, String> {
+    match value {
+        "1" => Ok(MeshLevel::First),
+        "2" => Ok(MeshLevel::Second),
+        "3" => Ok(MeshLevel::Third),
+        _ => Err(format!("the mesh level must be 1, 2 or 3 ({value})")),
+    }
+}
+
+/// コマンドライン引数で指定された、格子点を領域に含める条件を解析する。
+fn parse_area_mode(value: &str) -> Result<AreaMode, String> {
+    match value {
+        "center" => Ok(AreaMode::CellCenter),
+        "intersects" => Ok(AreaMode::CellIntersects),
+        _ => Err(format!(
+            "the area mode must be center or intersects ({value})"
+        )),
+    }
+}
+
+/// コマンドライン引数で指定された、格子点を出力する地域メッシュ・コードの集合を返却する。
+///
+/// 地域メッシュ・コードが指定されていない場合は`None`を返却する。
+/// `--mesh-codes 5339,`のような、カンマ区切りの空の要素は無視する。
+fn mesh_codes(args: &Args) -> Option<MeshCodes> {
+    let arg_codes: Vec<&str> = args
+        .mesh_codes
+        .iter()
+        .map(|code| code.trim())
+        .filter(|code| !code.is_empty())
+        .collect();
+    if arg_codes.is_empty() && args.mesh_codes_file.is_none() {
+        return None;
+    }
+    let mut codes = match &args.mesh_codes_file {
+        Some(path) => MeshCodes::from_file(path).unwrap(),
+        None => MeshCodes::default(),
+    };
+    for code in arg_codes {
+        codes.insert(code).unwrap();
+    }
+
+    Some(codes)
+}
+
+/// コマンドライン引数で指定された出力CSVファイルのパスを返却する。
+fn output_path(args: &Args) -> &Path {
+    Path::new(
+        args.output
+            .as_deref()
+            .expect("the output csv file path is required"),
+    )
+}
+
+/// コマンドライン引数で指定されたGRIB2報を、CSVファイルに変換する。
+///
+/// 統計量を集計する領域が指定された場合は、格子点の代わりに領域ごとの統計量を出力する。
+fn convert<R: BufRead + Seek, P: AsRef<Path>>(
+    converter: &Grib2Csv<R>,
+    args: &Args,
+    output: P,
+    boundary: Boundary,
+    zones: Option<&[Zone]>,
+) {
+    // 変換するGRIB2報のインデックスを決定
+    let indexes: Vec<usize> = if args.all_messages {
+        (0..converter.number_of_messages()).collect()
+    } else if args.messages.is_empty() {
+        vec![0]
+    } else {
+        args.messages
+            .iter()
+            .map(|&number| {
+                if number == 0 {
+                    panic!("the message number must be greater than or equal to 1");
+                }
+                number - 1
+            })
+            .collect()
+    };
+    let convert_message = |index: usize, output: &Path| match zones {
+        Some(zones) => converter
+            .convert_zonal_statistics_message(index, output, zones, args.area_mode)
+            .unwrap(),
+        None => converter
+            .convert_message(index, output, boundary.clone())
+            .unwrap(),
+    };
+    if indexes.len() == 1 {
+        convert_message(indexes[0], output.as_ref());
+    } else {
+        for index in indexes {
+            convert_message(index, &message_output_path(&output, index));
+        }
+    }
+}
+
+/// 複数のGRIB2報を変換するときに、GRIB2報ごとの出力CSVファイルのパスを返却する。
+///
+/// 出力CSVファイルのファイル名の末尾に、1から始まるGRIB2報の番号を付与する。
+/// 例えば、`output.csv`の2番目のGRIB2報は`output_02.csv`に出力する。
+fn message_output_path<P: AsRef<Path>>(path: P, index: usize) -> PathBuf {
+    suffixed_output_path(path, &format!("{:02}", index + 1))
+}
+
+/// tarアーカイブに格納されている複数のGRIB2ファイルを変換するときに、GRIB2ファイルごとの出力CSVファイルのパスを返却する。
+///
+/// 出力CSVファイルのファイル名の末尾に、最初のGRIB2報の第1節に記録されている参照時刻を付与する。
+/// 例えば、`output.csv`の参照時刻が2022年8月12日12時00分のGRIB2ファイルは`output_202208121200.csv`に出力する。
+fn member_output_path<P: AsRef<Path>>(path: P, referenced_at: PrimitiveDateTime) -> PathBuf {
+    suffixed_output_path(path, &reference_time_label(referenced_at))
+}
+
+/// 参照時刻を、年月日時分を連結した文字列（例: 202208121200）で返却する。
+fn reference_time_label(referenced_at: PrimitiveDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}{:02}{:02}",
+        referenced_at.year(),
+        referenced_at.month() as u8,
+        referenced_at.day(),
+        referenced_at.hour(),
+        referenced_at.minute()
+    )
+}
+
+/// 出力CSVファイルのファイル名の末尾に、アンダースコアと文字列を付与したパスを返却する。
+fn suffixed_output_path<P: AsRef<Path>>(path: P, suffix: &str) -> PathBuf {
+    let path = path.as_ref();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{stem}_{suffix}.{}", ext.to_string_lossy()),
+        None => format!("{stem}_{suffix}"),
+    };
+
+    path.with_file_name(file_name)
 }
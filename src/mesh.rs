@@ -0,0 +1,321 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::anyhow;
+
+/// 地域メッシュの区画の種類（JIS X 0410）
+///
+/// 1kmメッシュ解析雨量や降水短時間予報の格子は、3次メッシュ（基準地域メッシュ）と一致する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshLevel {
+    /// 1次メッシュ（緯度40分、経度1度、4桁）
+    First,
+    /// 2次メッシュ（緯度5分、経度7分30秒、6桁）
+    Second,
+    /// 3次メッシュ（緯度30秒、経度45秒、8桁）
+    Third,
+}
+
+impl MeshLevel {
+    /// 地域メッシュ・コードの桁数から、区画の種類を返却する。
+    fn from_digits(digits: usize) -> Option<Self> {
+        match digits {
+            4 => Some(Self::First),
+            6 => Some(Self::Second),
+            8 => Some(Self::Third),
+            _ => None,
+        }
+    }
+
+    /// 1次メッシュを、緯線方向と経線方向にそれぞれ分割した数を返却する。
+    fn divisions(&self) -> i64 {
+        match self {
+            Self::First => 1,
+            Self::Second => 8,
+            Self::Third => 80,
+        }
+    }
+
+    /// 地域メッシュ・コードの桁数を返却する。
+    pub fn digits(&self) -> usize {
+        match self {
+            Self::First => 4,
+            Self::Second => 6,
+            Self::Third => 8,
+        }
+    }
+
+    /// 区画の種類ごとの地域メッシュ・コードの集合のインデックスを返却する。
+    fn index(&self) -> usize {
+        match self {
+            Self::First => 0,
+            Self::Second => 1,
+            Self::Third => 2,
+        }
+    }
+}
+
+/// 地域メッシュ・コードの集合
+///
+/// 1次、2次及び3次メッシュ・コードを混在して登録でき、格子点がいずれかの地域メッシュに含まれるか確認する。
+/// 地域メッシュ・コードは区画の種類ごとにハッシュ集合で管理するため、登録した地域メッシュ・コードの数に関わらず、
+/// 格子点ごとに区画の種類の数だけ地域メッシュ・コードを求めて検索する。
+#[derive(Debug, Clone, Default)]
+pub struct MeshCodes {
+    /// 1次、2次及び3次メッシュ・コードの集合
+    codes: [HashSet<u32>; 3],
+}
+
+impl MeshCodes {
+    /// カンマ、空白または改行で区切られた地域メッシュ・コードを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `text` - 地域メッシュ・コードを区切った文字列（例: `5339,533946,53394611`）。
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut codes = Self::default();
+        for code in text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|code| !code.is_empty())
+        {
+            codes.insert(code)?;
+        }
+
+        Ok(codes)
+    }
+
+    /// カンマ、空白または改行で区切られた地域メッシュ・コードを記録したファイルを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 地域メッシュ・コードを記録したファイルのパス。
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+
+        Self::parse(&text).map_err(|e| anyhow!("failed to read {}: {e}", path.display()))
+    }
+
+    /// 地域メッシュ・コードを登録する。
+    ///
+    /// 区画の種類は、地域メッシュ・コードの桁数（4桁、6桁または8桁）で判定する。
+    ///
+    /// # 引数
+    ///
+    /// * `code` - 地域メッシュ・コード。
+    pub fn insert(&mut self, code: &str) -> anyhow::Result<()> {
+        let level = MeshLevel::from_digits(code.len())
+            .filter(|_| code.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| anyhow!("invalid mesh code: {code}"))?;
+        let code: u32 = code.parse()?;
+        if mesh_center(code, level).is_none() {
+            return Err(anyhow!(
+                "invalid mesh code: {code:0width$}",
+                width = level.digits()
+            ));
+        }
+        self.codes[level.index()].insert(code);
+
+        Ok(())
+    }
+
+    /// 登録されている地域メッシュ・コードの数を返却する。
+    pub fn len(&self) -> usize {
+        self.codes.iter().map(|codes| codes.len()).sum()
+    }
+
+    /// 地域メッシュ・コードが登録されていないか確認する。
+    pub fn is_empty(&self) -> bool {
+        self.codes.iter().all(|codes| codes.is_empty())
+    }
+
+    /// 経度と緯度が、登録されているいずれかの地域メッシュに含まれるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `longitude` - 経度（10^6度単位、西経は負）。
+    /// * `latitude` - 緯度（10^6度単位、南緯は負）。
+    pub fn contains(&self, longitude: i32, latitude: i32) -> bool {
+        [MeshLevel::First, MeshLevel::Second, MeshLevel::Third]
+            .into_iter()
+            .filter(|level| !self.codes[level.index()].is_empty())
+            .any(|level| {
+                mesh_code(longitude, latitude, level)
+                    .is_some_and(|code| self.codes[level.index()].contains(&code))
+            })
+    }
+}
+
+/// 1次メッシュの緯度方向の大きさ（10^6度単位）の分子と分母（40分 = 2/3度）
+const FIRST_MESH_LATITUDE: (i64, i64) = (2_000_000, 3);
+/// 1次メッシュの経度方向の大きさ（10^6度単位）
+const FIRST_MESH_LONGITUDE: i64 = 1_000_000;
+/// 1次メッシュ・コードの下2桁が0となる経度（10^6度単位）
+const ORIGIN_LONGITUDE: i64 = 100_000_000;
+
+/// 経度と緯度を含む地域メッシュの、地域メッシュ・コードを返却する。
+///
+/// 地域メッシュ・コードを定義できない経度と緯度（南緯、北緯66度40分以北、東経100度未満、
+/// 東経200度以東）の場合は`None`を返却する。
+///
+/// # 引数
+///
+/// * `longitude` - 経度（10^6度単位、西経は負）。
+/// * `latitude` - 緯度（10^6度単位、南緯は負）。
+/// * `level` - 地域メッシュの区画の種類。
+pub fn mesh_code(longitude: i32, latitude: i32, level: MeshLevel) -> Option<u32> {
+    let divisions = level.divisions();
+    // 緯度0度と東経100度から数えた、地域メッシュの数
+    let (numerator, denominator) = FIRST_MESH_LATITUDE;
+    let row = (latitude as i64 * divisions * denominator).div_euclid(numerator);
+    let column =
+        ((longitude as i64 - ORIGIN_LONGITUDE) * divisions).div_euclid(FIRST_MESH_LONGITUDE);
+    if row < 0 || 100 * divisions <= row || column < 0 || 100 * divisions <= column {
+        return None;
+    }
+    // 1次メッシュ・コード
+    let mut code = (row / divisions) * 100 + column / divisions;
+    // 2次メッシュ・コード
+    if level != MeshLevel::First {
+        let (row, column) = (
+            row % divisions / (divisions / 8),
+            column % divisions / (divisions / 8),
+        );
+        code = code * 100 + row * 10 + column;
+    }
+    // 3次メッシュ・コード
+    if level == MeshLevel::Third {
+        code = code * 100 + (row % 10) * 10 + column % 10;
+    }
+
+    Some(code as u32)
+}
+
+/// 地域メッシュ・コードが示す地域メッシュの、中心の経度と緯度（10^6度単位）を返却する。
+///
+/// 3次メッシュの中心は、1kmメッシュ解析雨量や降水短時間予報の格子点と一致する。
+/// 地域メッシュ・コードの桁数が区画の種類と異なる場合や、2次メッシュの区画の番号が8以上の場合は
+/// `None`を返却する。
+///
+/// # 引数
+///
+/// * `code` - 地域メッシュ・コード。
+/// * `level` - 地域メッシュの区画の種類。
+pub fn mesh_center(code: u32, level: MeshLevel) -> Option<(i32, i32)> {
+    let code = code as i64;
+    if 10i64.pow(level.digits() as u32) <= code {
+        return None;
+    }
+    // 緯度0度と東経100度から数えた、地域メッシュの数
+    let (row, column) = match level {
+        MeshLevel::First => (code / 100, code % 100),
+        MeshLevel::Second | MeshLevel::Third => {
+            let second = if level == MeshLevel::Second {
+                code
+            } else {
+                code / 100
+            };
+            let (row, column) = ((second / 10) % 10, second % 10);
+            if 8 <= row || 8 <= column {
+                return None;
+            }
+            let (row, column) = (second / 10_000 * 8 + row, (second / 100) % 100 * 8 + column);
+            if level == MeshLevel::Second {
+                (row, column)
+            } else {
+                (row * 10 + (code / 10) % 10, column * 10 + code % 10)
+            }
+        }
+    };
+    // 地域メッシュの南西端から、大きさの半分だけ北東に移動した位置を、10^-6度単位に四捨五入
+    let divisions = level.divisions();
+    let (numerator, denominator) = FIRST_MESH_LATITUDE;
+    let latitude =
+        ((2 * row + 1) * numerator + divisions * denominator) / (2 * divisions * denominator);
+    let longitude =
+        ORIGIN_LONGITUDE + ((2 * column + 1) * FIRST_MESH_LONGITUDE + divisions) / (2 * divisions);
+
+    Some((longitude as i32, latitude as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mesh_code_of_tokyo_station() {
+        // 東京駅（北緯35.681度、東経139.767度）
+        assert_eq!(mesh_code(139767000, 35681000, MeshLevel::First), Some(5339));
+        assert_eq!(
+            mesh_code(139767000, 35681000, MeshLevel::Second),
+            Some(533946)
+        );
+        assert_eq!(
+            mesh_code(139767000, 35681000, MeshLevel::Third),
+            Some(53394611)
+        );
+        // 地域メッシュの南西端は、その地域メッシュに含まれる
+        assert_eq!(mesh_code(139000000, 35333334, MeshLevel::First), Some(5339));
+        assert_eq!(mesh_code(139000000, 35333333, MeshLevel::First), Some(5239));
+        // 地域メッシュ・コードを定義できない経度と緯度
+        assert_eq!(mesh_code(99999999, 35000000, MeshLevel::Third), None);
+        assert_eq!(mesh_code(139767000, -1, MeshLevel::Third), None);
+        assert_eq!(mesh_code(139767000, 66666667, MeshLevel::First), None);
+    }
+
+    #[test]
+    fn mesh_center_is_grid_point() {
+        // サンプルファイルの最初と最後の格子点
+        assert_eq!(
+            mesh_code(118006250, 47995833, MeshLevel::Third),
+            Some(71187090)
+        );
+        assert_eq!(
+            mesh_center(71187090, MeshLevel::Third),
+            Some((118006250, 47995833))
+        );
+        assert_eq!(
+            mesh_code(149993750, 20004167, MeshLevel::Third),
+            Some(30490709)
+        );
+        assert_eq!(
+            mesh_center(30490709, MeshLevel::Third),
+            Some((149993750, 20004167))
+        );
+        assert_eq!(
+            mesh_center(533946, MeshLevel::Second),
+            Some((139812500, 35708333))
+        );
+        assert_eq!(
+            mesh_center(5339, MeshLevel::First),
+            Some((139500000, 35666667))
+        );
+        // 桁数が区画の種類と異なる地域メッシュ・コードと、2次メッシュの区画の番号が8以上の地域メッシュ・コード
+        assert_eq!(mesh_center(533946, MeshLevel::First), None);
+        assert_eq!(mesh_center(533986, MeshLevel::Second), None);
+        assert_eq!(mesh_center(53398611, MeshLevel::Third), None);
+    }
+
+    #[test]
+    fn mesh_codes_contain_points_in_meshes() {
+        // 1次メッシュ5240、2次メッシュ533946及び3次メッシュ53394611
+        let codes = MeshCodes::parse("5240,\n533946 53394611\n").unwrap();
+        assert_eq!(codes.len(), 3);
+        // 東京駅
+        assert!(codes.contains(139767000, 35681000));
+        // 533946に含まれ、53394611に含まれない
+        assert!(codes.contains(139800000, 35700000));
+        // 5240に含まれる
+        assert!(codes.contains(140500000, 35000000));
+        // 5339に含まれるが、533946に含まれない
+        assert!(!codes.contains(139100000, 35400000));
+        assert!(!codes.contains(99000000, 35000000));
+        assert!(MeshCodes::default().is_empty());
+        // 桁数が誤っている地域メッシュ・コードや、数字以外を含む地域メッシュ・コード
+        assert!(MeshCodes::parse("53394").is_err());
+        assert!(MeshCodes::parse("5339461a").is_err());
+        assert!(MeshCodes::parse("533986").is_err());
+        assert!(MeshCodes::parse("+5339").is_err());
+    }
+}
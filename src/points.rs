@@ -0,0 +1,150 @@
+use std::cell::RefMut;
+use std::io::{BufRead, Seek};
+
+use anyhow::anyhow;
+
+use crate::bit_reader::BitReader;
+use crate::bitmap::is_defined;
+use crate::decoder::{ValueDecoder, ValueRun};
+use crate::mesh::{mesh_code, MeshLevel};
+use crate::Grib2Message;
+
+/// 格子点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridPoint {
+    /// 経度（10^6度単位、西経は負）
+    pub longitude: i32,
+    /// 緯度（10^6度単位、南緯は負）
+    pub latitude: i32,
+    /// 最初の格子点から数えたi方向（緯線またはx軸）の位置
+    pub i: u32,
+    /// 最初の格子点から数えたj方向（経線またはy軸）の位置
+    pub j: u32,
+    /// レベル値。ランレングス圧縮（資料表現テンプレート5.200）以外の場合、またはビットマップで除外された場合は`None`
+    pub level: Option<u16>,
+    /// 物理値。欠測値またはビットマップで除外された場合は`None`
+    pub value: Option<f64>,
+}
+
+impl GridPoint {
+    /// 格子点を含む地域メッシュ（JIS X 0410）のコードを返却する。
+    ///
+    /// 地域メッシュ・コードを定義できない格子点の場合は`None`を返却する。
+    pub fn mesh_code(&self, level: MeshLevel) -> Option<u32> {
+        mesh_code(self.longitude, self.latitude, level)
+    }
+}
+
+/// GRIB2報の第7節を展開しながら、資料の並び順で格子点を返却するイテレーター
+///
+/// 圧縮された第7節の資料値をメモリに読み込み、格子点を返却するたびに必要な分だけ展開するため、
+/// 格子点の数に関わらず展開した物理値を保持しない。
+/// すべての格子点を返却した後、第8節を読み込んでGRIB2報を正確に読み込めたか確認する。
+/// イテレーターが存在する間は、GRIB2ファイル・コンバーターのリーダーを借用する。
+pub struct GridPoints<'a, R: BufRead + Seek> {
+    /// GRIB2報
+    message: &'a Grib2Message,
+    /// GRIB2ファイルのリーダー
+    reader: RefMut<'a, R>,
+    /// 第7節の資料値を読み込むリーダー
+    bits: BitReader<Vec<u8>>,
+    /// 資料値のデコーダー
+    decoder: ValueDecoder,
+    /// 第6節のビットマップ
+    bitmap: Option<Vec<u8>>,
+    /// 展開中の連続する資料値
+    run: ValueRun,
+    /// 次に返却する格子点の、資料の並び順でのインデックス
+    index: u32,
+    /// すべての格子点を返却したか、読み込みに失敗したかを示すフラグ
+    finished: bool,
+}
+
+impl<'a, R: BufRead + Seek> GridPoints<'a, R> {
+    /// 第7節の資料値を読み込んで、イテレーターを作成する。
+    pub(crate) fn new(
+        message: &'a Grib2Message,
+        mut reader: RefMut<'a, R>,
+    ) -> anyhow::Result<Self> {
+        let (bitmap, data) = message.read_section7(&mut *reader)?;
+        let decoder = ValueDecoder::new(&message.metadata.section5, &data)?;
+
+        Ok(Self {
+            message,
+            reader,
+            bits: BitReader::new(data),
+            decoder,
+            bitmap,
+            run: ValueRun::default(),
+            index: 0,
+            finished: false,
+        })
+    }
+
+    /// 次の格子点を返却する。すべての格子点を返却した場合は、第8節を読み込んで`None`を返却する。
+    fn next_point(&mut self) -> anyhow::Result<Option<GridPoint>> {
+        let section3 = &self.message.metadata.section3;
+        if self.index == section3.number_of_points {
+            self.finish()?;
+            return Ok(None);
+        }
+        let defined = self
+            .bitmap
+            .as_deref()
+            .is_none_or(|bitmap| is_defined(bitmap, self.index));
+        let (level, value) = if defined {
+            while self.run.count == 0 {
+                self.run = self.decoder.next(&mut self.bits)?.ok_or_else(|| {
+                    anyhow!(
+                        "failed to read points (expected:{}, read:{})",
+                        section3.number_of_points,
+                        self.index
+                    )
+                })?;
+            }
+            self.run.count -= 1;
+            (self.run.level, self.run.value)
+        } else {
+            // ビットマップで除外された格子点
+            (None, None)
+        };
+        let (i, j) = section3.grid_index(self.index);
+        let (longitude, latitude) = section3.point_coordinate(i, j);
+        self.index += 1;
+
+        Ok(Some(GridPoint {
+            longitude,
+            latitude,
+            i,
+            j,
+            level,
+            value,
+        }))
+    }
+
+    /// すべての資料値を格子点に割り当てたことを確認して、第8節を読み込む。
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if 0 < self.run.count || self.decoder.next(&mut self.bits)?.is_some() {
+            return Err(anyhow!(
+                "the number of values exceeds the number of points (number of points:{})",
+                self.message.metadata.section3.number_of_points
+            ));
+        }
+
+        self.message.read_message_end(&mut *self.reader)
+    }
+}
+
+impl<R: BufRead + Seek> Iterator for GridPoints<'_, R> {
+    type Item = anyhow::Result<GridPoint>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = self.next_point();
+        self.finished = !matches!(result, Ok(Some(_)));
+
+        result.transpose()
+    }
+}
@@ -0,0 +1,412 @@
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+/// 逆投影で緯度を求めるときの反復計算の最大回数
+const MAX_ITERATIONS: usize = 20;
+/// 逆投影で緯度を求めるときの反復計算の収束判定に使用する値（ラジアン）
+const TOLERANCE: f64 = 1e-12;
+
+/// 地球楕円体
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Ellipsoid {
+    /// 長半径（メートル）
+    pub(crate) semi_major_axis: f64,
+    /// 離心率
+    pub(crate) eccentricity: f64,
+}
+
+impl Ellipsoid {
+    /// GRS80回転楕円体を返却する。
+    pub(crate) fn grs80() -> Self {
+        Self::from_flattening(6_378_137.0, 298.257_222_101)
+    }
+
+    /// 長半径と扁平率の逆数から地球楕円体を作成する。
+    pub(crate) fn from_flattening(semi_major_axis: f64, inverse_flattening: f64) -> Self {
+        let flattening = 1.0 / inverse_flattening;
+        Self {
+            semi_major_axis,
+            eccentricity: (flattening * (2.0 - flattening)).sqrt(),
+        }
+    }
+
+    /// 長半径と短半径から地球楕円体を作成する。
+    ///
+    /// 長半径と短半径が等しい場合は、球体として扱う。
+    pub(crate) fn from_axes(semi_major_axis: f64, semi_minor_axis: f64) -> Self {
+        let ratio = semi_minor_axis / semi_major_axis;
+        Self {
+            semi_major_axis,
+            eccentricity: (1.0 - ratio * ratio).max(0.0).sqrt(),
+        }
+    }
+
+    /// 短半径（メートル）を返却する。
+    pub(crate) fn semi_minor_axis(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.eccentricity * self.eccentricity).sqrt()
+    }
+
+    /// 2つの緯線と2つの経線で囲まれた領域の面積（平方メートル）を返却する。
+    ///
+    /// # 引数
+    ///
+    /// * `south` - 南端の緯度（度）。
+    /// * `north` - 北端の緯度（度）。
+    /// * `width` - 東端と西端の経度の差（度）。
+    pub(crate) fn area(&self, south: f64, north: f64, width: f64) -> f64 {
+        let b = self.semi_minor_axis();
+        b * b * width.to_radians() / 2.0 * (self.q(north.to_radians()) - self.q(south.to_radians()))
+    }
+
+    /// 赤道から緯度までの帯の面積の計算に使用する関数qの値を返却する。
+    fn q(&self, latitude: f64) -> f64 {
+        let e = self.eccentricity;
+        let sin = latitude.sin();
+        if e < TOLERANCE {
+            // 球体の場合の極限値
+            return 2.0 * sin;
+        }
+        let e_sin = e * sin;
+
+        sin / (1.0 - e_sin * e_sin) + ((1.0 + e_sin) / (1.0 - e_sin)).ln() / (2.0 * e)
+    }
+
+    /// 等角緯度の計算に使用する関数tの値を返却する。
+    fn t(&self, latitude: f64) -> f64 {
+        let e = self.eccentricity;
+        let e_sin = e * latitude.sin();
+        (FRAC_PI_4 - latitude / 2.0).tan() / ((1.0 - e_sin) / (1.0 + e_sin)).powf(e / 2.0)
+    }
+
+    /// 緯線の半径を長半径で除した値（関数m）を返却する。
+    fn m(&self, latitude: f64) -> f64 {
+        let e_sin = self.eccentricity * latitude.sin();
+        latitude.cos() / (1.0 - e_sin * e_sin).sqrt()
+    }
+
+    /// 関数tの値から、緯度を反復計算で求める。
+    fn latitude_from_t(&self, t: f64) -> f64 {
+        let e = self.eccentricity;
+        let mut latitude = FRAC_PI_2 - 2.0 * t.atan();
+        for _ in 0..MAX_ITERATIONS {
+            let e_sin = e * latitude.sin();
+            let next = FRAC_PI_2 - 2.0 * (t * ((1.0 - e_sin) / (1.0 + e_sin)).powf(e / 2.0)).atan();
+            let converged = (next - latitude).abs() < TOLERANCE;
+            latitude = next;
+            if converged {
+                break;
+            }
+        }
+
+        latitude
+    }
+}
+
+/// 円錐図法による投影
+///
+/// ランベルト正角円錐図法は円錐定数が1未満の円錐図法、ポーラーステレオ図法は円錐定数が1の円錐図法として扱う。
+/// 投影面の座標は、円錐の頂点（ポーラーステレオ図法の場合は極）を原点とする。
+#[derive(Debug, Clone)]
+pub(crate) struct ConicProjection {
+    /// 地球楕円体
+    ellipsoid: Ellipsoid,
+    /// 円錐定数（n）
+    n: f64,
+    /// 投影定数（F）
+    f: f64,
+    /// 中心経線の経度（ラジアン）
+    central_longitude: f64,
+    /// 北極を中心とする場合は1、南極を中心とする場合は-1
+    hemisphere: f64,
+}
+
+impl ConicProjection {
+    /// ランベルト正角円錐図法の投影を作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `ellipsoid` - 地球楕円体。
+    /// * `latin1` - 第1標準緯線（度）。
+    /// * `latin2` - 第2標準緯線（度）。
+    /// * `lov` - 中心経線の経度（度）。
+    pub(crate) fn lambert_conformal(
+        ellipsoid: Ellipsoid,
+        latin1: f64,
+        latin2: f64,
+        lov: f64,
+    ) -> Self {
+        let (phi1, phi2) = (latin1.to_radians(), latin2.to_radians());
+        let (m1, t1) = (ellipsoid.m(phi1), ellipsoid.t(phi1));
+        let n = if (phi1 - phi2).abs() < TOLERANCE {
+            phi1.sin()
+        } else {
+            let (m2, t2) = (ellipsoid.m(phi2), ellipsoid.t(phi2));
+            (m1.ln() - m2.ln()) / (t1.ln() - t2.ln())
+        };
+
+        Self {
+            ellipsoid,
+            n,
+            f: m1 / (n * t1.powf(n)),
+            central_longitude: lov.to_radians(),
+            hemisphere: 1.0,
+        }
+    }
+
+    /// ポーラーステレオ図法の投影を作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `ellipsoid` - 地球楕円体。
+    /// * `lad` - 縮尺が正しい緯度（度）。南極を中心とする場合は負の値で指定する。
+    /// * `lov` - 中心経線の経度（度）。
+    /// * `south_pole` - 南極を中心とする場合は`true`。
+    pub(crate) fn polar_stereographic(
+        ellipsoid: Ellipsoid,
+        lad: f64,
+        lov: f64,
+        south_pole: bool,
+    ) -> Self {
+        let hemisphere = if south_pole { -1.0 } else { 1.0 };
+        let phi_c = (hemisphere * lad).to_radians();
+        let e = ellipsoid.eccentricity;
+        let f = if (FRAC_PI_2 - phi_c).abs() < TOLERANCE {
+            // 極で縮尺が正しい場合
+            2.0 / ((1.0 + e).powf(1.0 + e) * (1.0 - e).powf(1.0 - e)).sqrt()
+        } else {
+            ellipsoid.m(phi_c) / ellipsoid.t(phi_c)
+        };
+
+        Self {
+            ellipsoid,
+            n: 1.0,
+            f,
+            central_longitude: hemisphere * lov.to_radians(),
+            hemisphere,
+        }
+    }
+
+    /// 緯度と経度（度）を、投影面の座標（メートル）に変換する。
+    pub(crate) fn forward(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        let phi = self.hemisphere * latitude.to_radians();
+        let lambda = self.hemisphere * longitude.to_radians();
+        let rho = self.rho(phi);
+        let theta = self.n * normalize_radian(lambda - self.central_longitude);
+
+        (
+            self.hemisphere * rho * theta.sin(),
+            -self.hemisphere * rho * theta.cos(),
+        )
+    }
+
+    /// 投影面の座標（メートル）を、緯度と経度（度）に変換する。
+    ///
+    /// 経度は0度以上360度未満に正規化する。
+    pub(crate) fn inverse(&self, x: f64, y: f64) -> (f64, f64) {
+        let (x, y) = (self.hemisphere * x, self.hemisphere * y);
+        let sign = self.n.signum();
+        let rho = sign * x.hypot(y);
+        let theta = (sign * x).atan2(-sign * y);
+        let t = (rho / (self.ellipsoid.semi_major_axis * self.f)).powf(1.0 / self.n);
+        let phi = self.ellipsoid.latitude_from_t(t);
+        let lambda = theta / self.n + self.central_longitude;
+
+        (
+            (self.hemisphere * phi).to_degrees(),
+            (self.hemisphere * lambda).to_degrees().rem_euclid(360.0),
+        )
+    }
+
+    /// 緯度（度）における縮尺係数を返却する。
+    pub(crate) fn scale_factor(&self, latitude: f64) -> f64 {
+        let phi = self.hemisphere * latitude.to_radians();
+        let m = self.ellipsoid.m(phi);
+        if m.abs() < TOLERANCE {
+            // 極で縮尺が正しいポーラーステレオ図法
+            return 1.0;
+        }
+
+        self.rho(phi) * self.n / (self.ellipsoid.semi_major_axis * m)
+    }
+
+    /// 緯度（ラジアン）に対応する、円錐の頂点からの距離を返却する。
+    fn rho(&self, phi: f64) -> f64 {
+        self.ellipsoid.semi_major_axis * self.f * self.ellipsoid.t(phi).powf(self.n)
+    }
+}
+
+/// ラジアンで表現された角度を、-πからπの範囲に正規化する。
+fn normalize_radian(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(2.0 * std::f64::consts::PI);
+    if std::f64::consts::PI < angle {
+        angle - 2.0 * std::f64::consts::PI
+    } else {
+        angle
+    }
+}
+
+/// 投影面上で、格子点が等間隔に並んだ格子
+#[derive(Debug, Clone)]
+pub(crate) struct ProjectedPlane {
+    /// 投影
+    projection: ConicProjection,
+    /// 最初の格子点の投影面の座標（メートル）
+    origin: (f64, f64),
+    /// 投影面上のx方向の格子間隔（メートル）
+    dx: f64,
+    /// 投影面上のy方向の格子間隔（メートル）
+    dy: f64,
+}
+
+impl ProjectedPlane {
+    /// 投影面上の格子を作成する。
+    ///
+    /// # 引数
+    ///
+    /// * `projection` - 投影。
+    /// * `first_latitude` - 最初の格子点の緯度（度）。
+    /// * `first_longitude` - 最初の格子点の経度（度）。
+    /// * `dx` - 縮尺が正しい緯度におけるx方向の格子間隔（メートル）。
+    /// * `dy` - 縮尺が正しい緯度におけるy方向の格子間隔（メートル）。
+    /// * `lad` - 格子間隔が正しい緯度（度）。
+    pub(crate) fn new(
+        projection: ConicProjection,
+        first_latitude: f64,
+        first_longitude: f64,
+        dx: f64,
+        dy: f64,
+        lad: f64,
+    ) -> Self {
+        let origin = projection.forward(first_latitude, first_longitude);
+        let scale = projection.scale_factor(lad);
+
+        Self {
+            projection,
+            origin,
+            dx: dx * scale,
+            dy: dy * scale,
+        }
+    }
+
+    /// 緯度（度）にある格子の面積（平方メートル）を返却する。
+    ///
+    /// 正角図法であるため、地表の格子の辺の長さは、投影面上の格子間隔を縮尺係数で除した値になる。
+    pub(crate) fn cell_area(&self, latitude: f64) -> f64 {
+        let scale = self.projection.scale_factor(latitude);
+
+        self.dx * self.dy / (scale * scale)
+    }
+
+    /// 最初の格子点からx方向に`i`、y方向に`j`離れた格子点の、緯度と経度（度）を返却する。
+    pub(crate) fn coordinate(&self, i: f64, j: f64) -> (f64, f64) {
+        let x = self.origin.0 + i * self.dx;
+        let y = self.origin.1 + j * self.dy;
+
+        self.projection.inverse(x, y)
+    }
+
+    /// 緯度と経度（度）の地点の、最初の格子点から数えたx方向とy方向の格子数（小数を含む）を返却する。
+    pub(crate) fn grid_position(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        let (x, y) = self.projection.forward(latitude, longitude);
+
+        ((x - self.origin.0) / self.dx, (y - self.origin.1) / self.dy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64), tolerance: f64) {
+        assert!(
+            (actual.0 - expected.0).abs() < tolerance && (actual.1 - expected.1).abs() < tolerance,
+            "actual:{actual:?}, expected:{expected:?}"
+        );
+    }
+
+    #[test]
+    fn lambert_conformal_round_trip() {
+        // 気象庁のメソ数値予報モデルと同様の投影（標準緯線30度と60度、中心経線140度）
+        let projection = ConicProjection::lambert_conformal(Ellipsoid::grs80(), 30.0, 60.0, 140.0);
+        // 標準緯線上の縮尺係数は1
+        assert!((projection.scale_factor(30.0) - 1.0).abs() < 1e-12);
+        assert!((projection.scale_factor(60.0) - 1.0).abs() < 1e-12);
+        assert!(projection.scale_factor(45.0) < 1.0);
+        for (latitude, longitude) in [(20.0, 120.0), (35.0, 140.0), (47.5, 155.25), (60.0, 100.0)] {
+            let (x, y) = projection.forward(latitude, longitude);
+            assert_close(projection.inverse(x, y), (latitude, longitude), 1e-9);
+        }
+        // 中心経線上の点は、投影面のy軸上にある
+        let (x, _) = projection.forward(35.0, 140.0);
+        assert!(x.abs() < 1e-6);
+    }
+
+    #[test]
+    fn polar_stereographic_round_trip() {
+        // 北緯60度で縮尺が正しく、中心経線が東経140度
+        let projection =
+            ConicProjection::polar_stereographic(Ellipsoid::grs80(), 60.0, 140.0, false);
+        assert!((projection.scale_factor(60.0) - 1.0).abs() < 1e-12);
+        for (latitude, longitude) in [(30.0, 120.0), (60.0, 140.0), (89.0, 300.0)] {
+            let (x, y) = projection.forward(latitude, longitude);
+            assert_close(projection.inverse(x, y), (latitude, longitude), 1e-9);
+        }
+        // 極は原点
+        let (x, y) = projection.forward(90.0, 0.0);
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+
+        // 極で縮尺が正しい場合
+        let projection = ConicProjection::polar_stereographic(Ellipsoid::grs80(), 90.0, 0.0, false);
+        assert_eq!(projection.scale_factor(90.0), 1.0);
+        let (x, y) = projection.forward(45.0, 90.0);
+        assert_close(projection.inverse(x, y), (45.0, 90.0), 1e-9);
+
+        // 南極を中心とする場合
+        let projection = ConicProjection::polar_stereographic(Ellipsoid::grs80(), -71.0, 0.0, true);
+        assert!((projection.scale_factor(-71.0) - 1.0).abs() < 1e-12);
+        for (latitude, longitude) in [(-60.0, 30.0), (-85.0, 200.0)] {
+            let (x, y) = projection.forward(latitude, longitude);
+            assert_close(projection.inverse(x, y), (latitude, longitude), 1e-9);
+        }
+    }
+
+    #[test]
+    fn area_of_sphere_and_ellipsoid() {
+        // 球体の表面積は4πR^2
+        let sphere = Ellipsoid::from_axes(6_371_229.0, 6_371_229.0);
+        assert_eq!(sphere.eccentricity, 0.0);
+        let expected = 4.0 * std::f64::consts::PI * 6_371_229f64.powi(2);
+        assert!((sphere.area(-90.0, 90.0, 360.0) / expected - 1.0).abs() < 1e-12);
+
+        // GRS80回転楕円体の表面積は約510,065,622平方キロメートル
+        let grs80 = Ellipsoid::grs80();
+        assert!((grs80.semi_minor_axis() - 6_356_752.314_140).abs() < 1e-3);
+        let area = grs80.area(-90.0, 90.0, 360.0) / 1e6;
+        assert!((area - 510_065_622.0).abs() < 1.0, "{area}");
+        // 北半球と南半球の面積は等しい
+        assert!((grs80.area(0.0, 90.0, 360.0) - grs80.area(-90.0, 0.0, 360.0)).abs() < 1.0);
+    }
+
+    #[test]
+    fn projected_plane_steps_by_grid_length() {
+        let projection = ConicProjection::lambert_conformal(Ellipsoid::grs80(), 30.0, 60.0, 140.0);
+        let plane = ProjectedPlane::new(projection.clone(), 22.4, 120.0, 5000.0, 5000.0, 60.0);
+        assert_close(plane.coordinate(0.0, 0.0), (22.4, 120.0), 1e-9);
+        // 標準緯線上では、隣接する格子点間の投影面上の距離は格子間隔と等しい
+        let (latitude, longitude) = plane.coordinate(1.0, 1.0);
+        let (x0, y0) = projection.forward(22.4, 120.0);
+        let (x1, y1) = projection.forward(latitude, longitude);
+        assert!((x1 - x0 - 5000.0).abs() < 1e-6);
+        assert!((y1 - y0 - 5000.0).abs() < 1e-6);
+        // 標準緯線以外で格子間隔が正しい場合、投影面上の距離は格子間隔に縮尺係数を乗じた値
+        let plane = ProjectedPlane::new(projection.clone(), 22.4, 120.0, 5000.0, 5000.0, 45.0);
+        let (latitude, longitude) = plane.coordinate(1.0, 0.0);
+        let (x1, _) = projection.forward(latitude, longitude);
+        let expected = 5000.0 * projection.scale_factor(45.0);
+        assert!((x1 - x0 - expected).abs() < 1e-6);
+        // 格子間隔が正しい緯度では、格子の面積は格子間隔の2乗
+        assert!((plane.cell_area(45.0) - 5000.0 * 5000.0).abs() < 1e-6);
+        // 格子点の座標から、最初の格子点から数えた格子数を求められる
+        let (latitude, longitude) = plane.coordinate(3.0, -2.0);
+        assert_close(plane.grid_position(latitude, longitude), (3.0, -2.0), 1e-9);
+    }
+}
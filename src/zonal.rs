@@ -0,0 +1,365 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use geojson::feature::Id;
+use geojson::{GeoJson, JsonValue};
+
+use crate::{Area, AreaMode, Section3};
+
+/// 格子と領域が重なる部分の面積の比率が、この値以下の場合は格子を領域に含めない
+const MIN_OVERLAP_RATIO: f64 = 1e-9;
+/// 領域を検索する索引の、東西または南北方向の区画数の目安
+const INDEX_DIVISIONS: f64 = 512.0;
+/// 領域を検索する索引の区画の、最小の大きさ（度）
+const MIN_INDEX_CELL_SIZE: f64 = 0.01;
+
+/// 物理値の統計量を集計する領域
+#[derive(Debug, Clone)]
+pub struct Zone {
+    /// 領域の識別子
+    pub id: String,
+    /// 領域
+    pub area: Area,
+}
+
+impl Zone {
+    /// GeoJSON形式のフィーチャー・コレクションから、フィーチャーごとの領域を読み込む。
+    ///
+    /// 領域の識別子は、フィーチャーのプロパティから読み込む。
+    /// フィーチャーがプロパティを持たない場合は、フィーチャーの`id`を識別子とする。
+    ///
+    /// # 引数
+    ///
+    /// * `text` - GeoJSON形式の文字列。
+    /// * `id_property` - 領域の識別子を記録したプロパティの名前。
+    pub fn parse(text: &str, id_property: &str) -> anyhow::Result<Vec<Self>> {
+        let geojson =
+            GeoJson::from_str(text).map_err(|e| anyhow!("failed to parse geojson: {e}"))?;
+        let GeoJson::FeatureCollection(collection) = geojson else {
+            return Err(anyhow!("the zones must be a geojson feature collection"));
+        };
+        if collection.features.is_empty() {
+            return Err(anyhow!(
+                "the feature collection does not contain any feature"
+            ));
+        }
+        collection
+            .features
+            .iter()
+            .enumerate()
+            .map(|(number, feature)| {
+                let id = match (feature.property(id_property), &feature.id) {
+                    (Some(JsonValue::String(id)), _) => id.clone(),
+                    (Some(value), _) if !value.is_null() => value.to_string(),
+                    (_, Some(Id::String(id))) => id.clone(),
+                    (_, Some(Id::Number(id))) => id.to_string(),
+                    _ => {
+                        return Err(anyhow!(
+                            "the feature {number} does not have the property {id_property}"
+                        ))
+                    }
+                };
+                let geometry = feature
+                    .geometry
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("the feature {id} does not have a geometry"))?;
+                let area = Area::from_geojson_geometry(geometry)
+                    .map_err(|e| anyhow!("failed to read the feature {id}: {e}"))?;
+
+                Ok(Self { id, area })
+            })
+            .collect()
+    }
+
+    /// GeoJSON形式のフィーチャー・コレクションを記録したファイルから、フィーチャーごとの領域を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - フィーチャー・コレクションを記録したファイルのパス。
+    /// * `id_property` - 領域の識別子を記録したプロパティの名前。
+    pub fn from_file<P: AsRef<Path>>(path: P, id_property: &str) -> anyhow::Result<Vec<Self>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))?;
+
+        Self::parse(&text, id_property)
+            .map_err(|e| anyhow!("failed to read {}: {e}", path.display()))
+    }
+}
+
+/// 領域に含まれる格子点の物理値の統計量
+///
+/// 欠測値またはビットマップで除外された格子点は、統計量に含めない。
+/// 統計量に含める格子点がない場合、統計量は`None`。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZonalStatistics {
+    /// 領域の識別子
+    pub id: String,
+    /// 統計量に含めた格子点の数
+    pub cells: u32,
+    /// 物理値の平均
+    pub mean: Option<f64>,
+    /// 物理値の最小値
+    pub min: Option<f64>,
+    /// 物理値の最大値
+    pub max: Option<f64>,
+    /// 格子の面積で重み付けした物理値の平均
+    ///
+    /// 格子点を中心とする格子が領域と交わる格子点を含める場合、格子の面積に、
+    /// 格子のうち領域と重なる部分の比率を乗じて重み付けする。
+    pub weighted_mean: Option<f64>,
+    /// 物理値が0より大きい格子が占める面積の比率
+    ///
+    /// 重み付け平均と同じ重みで、統計量に含めた格子の面積に対する比率を計算する。
+    pub rainy_coverage: Option<f64>,
+}
+
+/// 領域ごとに物理値を集計する集計器
+pub(crate) struct ZonalAccumulator<'a> {
+    /// 統計量を集計する領域
+    zones: &'a [Zone],
+    /// 格子点を領域に含める条件
+    mode: AreaMode,
+    /// 領域を検索する索引の区画の大きさ（度）
+    index_cell_size: f64,
+    /// 索引の区画ごとの、区画と重なる可能性がある領域のインデックス
+    index: HashMap<(i64, i64), Vec<usize>>,
+    /// 領域ごとの集計値
+    totals: Vec<ZoneTotal>,
+    /// 格子と重なる可能性がある領域のインデックス（作業領域）
+    candidates: Vec<usize>,
+}
+
+/// 領域に含まれる格子点の物理値の集計値
+#[derive(Debug, Clone, Copy)]
+struct ZoneTotal {
+    cells: u32,
+    sum: f64,
+    min: f64,
+    max: f64,
+    weighted_sum: f64,
+    weight: f64,
+    rainy_weight: f64,
+}
+
+impl<'a> ZonalAccumulator<'a> {
+    /// 集計器を作成する。
+    ///
+    /// 格子点ごとにすべての領域を確認しないように、領域を囲む矩形で、経度と緯度の区画に領域を登録した索引を作成する。
+    pub(crate) fn new(zones: &'a [Zone], mode: AreaMode) -> Self {
+        let (west, south, east, north) = zones.iter().flat_map(|zone| zone.area.bounds()).fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(w, s, e, n), (west, south, east, north)| {
+                (w.min(west), s.min(south), e.max(east), n.max(north))
+            },
+        );
+        let index_cell_size =
+            ((east - west).max(north - south) / INDEX_DIVISIONS).max(MIN_INDEX_CELL_SIZE);
+        let mut accumulator = Self {
+            zones,
+            mode,
+            index_cell_size,
+            index: HashMap::new(),
+            totals: vec![ZoneTotal::default(); zones.len()],
+            candidates: vec![],
+        };
+        for (number, zone) in zones.iter().enumerate() {
+            for (west, south, east, north) in zone.area.bounds() {
+                let (x0, y0) = accumulator.index_cell(west, south);
+                let (x1, y1) = accumulator.index_cell(east, north);
+                for x in x0..=x1 {
+                    for y in y0..=y1 {
+                        let numbers = accumulator.index.entry((x, y)).or_default();
+                        if numbers.last() != Some(&number) {
+                            numbers.push(number);
+                        }
+                    }
+                }
+            }
+        }
+
+        accumulator
+    }
+
+    /// 資料の並び順で`index`番目の格子点の物理値を、格子点を含む領域に集計する。
+    pub(crate) fn add(&mut self, section3: &Section3, index: u32, value: f64) {
+        let (longitude, latitude) = section3.coordinate(index);
+        let (longitude, latitude) = (
+            longitude as f64 / 1_000_000f64,
+            latitude as f64 / 1_000_000f64,
+        );
+        let mut cell_area = None;
+        match self.mode {
+            AreaMode::CellCenter => {
+                let Some(numbers) = self.index.get(&self.index_cell(longitude, latitude)) else {
+                    return;
+                };
+                for &number in numbers {
+                    if self.zones[number].area.contains(longitude, latitude) {
+                        let weight = *cell_area.get_or_insert_with(|| section3.cell_area(index));
+                        self.totals[number].add(value, weight);
+                    }
+                }
+            }
+            AreaMode::CellIntersects => {
+                // 格子を囲む矩形と重なる索引の区画から、格子と重なる可能性がある領域を列挙
+                let corners = section3.cell_corners(index);
+                let (west, south, east, north) = corners.iter().fold(
+                    (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+                    |(w, s, e, n), &(x, y)| (w.min(x), s.min(y), e.max(x), n.max(y)),
+                );
+                let (x0, y0) = self.index_cell(west, south);
+                let (x1, y1) = self.index_cell(east, north);
+                self.candidates.clear();
+                for x in x0..=x1 {
+                    for y in y0..=y1 {
+                        if let Some(numbers) = self.index.get(&(x, y)) {
+                            self.candidates.extend(numbers);
+                        }
+                    }
+                }
+                self.candidates.sort_unstable();
+                self.candidates.dedup();
+                // 格子と領域が重なる部分の面積の比率で重み付け
+                for &number in &self.candidates {
+                    let ratio = self.zones[number].area.overlap_ratio(&corners);
+                    if MIN_OVERLAP_RATIO < ratio {
+                        let weight = *cell_area.get_or_insert_with(|| section3.cell_area(index));
+                        self.totals[number].add(value, weight * ratio);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 領域ごとの統計量を返却する。
+    pub(crate) fn finish(self) -> Vec<ZonalStatistics> {
+        self.zones
+            .iter()
+            .zip(self.totals)
+            .map(|(zone, total)| {
+                let has_cells = 0 < total.cells;
+                let has_weight = 0.0 < total.weight;
+                ZonalStatistics {
+                    id: zone.id.clone(),
+                    cells: total.cells,
+                    mean: has_cells.then(|| total.sum / total.cells as f64),
+                    min: has_cells.then_some(total.min),
+                    max: has_cells.then_some(total.max),
+                    weighted_mean: has_weight.then(|| total.weighted_sum / total.weight),
+                    rainy_coverage: has_weight.then(|| total.rainy_weight / total.weight),
+                }
+            })
+            .collect()
+    }
+
+    /// 経度と緯度（度）を含む、索引の区画を返却する。
+    fn index_cell(&self, longitude: f64, latitude: f64) -> (i64, i64) {
+        (
+            (longitude / self.index_cell_size).floor() as i64,
+            (latitude / self.index_cell_size).floor() as i64,
+        )
+    }
+}
+
+impl Default for ZoneTotal {
+    fn default() -> Self {
+        Self {
+            cells: 0,
+            sum: 0.0,
+            min: f64::MAX,
+            max: f64::MIN,
+            weighted_sum: 0.0,
+            weight: 0.0,
+            rainy_weight: 0.0,
+        }
+    }
+}
+
+impl ZoneTotal {
+    /// 格子点の物理値を、重みとともに集計する。
+    fn add(&mut self, value: f64, weight: f64) {
+        self.cells += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.weighted_sum += value * weight;
+        self.weight += weight;
+        if 0.0 < value {
+            self.rainy_weight += weight;
+        }
+    }
+}
+
+/// CSVファイルの項目に出力する文字列を返却する。
+///
+/// カンマ、二重引用符または改行を含む場合は、二重引用符で囲み、二重引用符を重ねる。
+pub(crate) fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_zones_from_feature_collection() {
+        let geojson = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": {"code": "13101", "name": "千代田区"},
+                    "geometry": {"type": "Polygon", "coordinates": [[[139, 35], [140, 35], [140, 36], [139, 35]]]}
+                },
+                {
+                    "type": "Feature",
+                    "id": 2,
+                    "properties": {"code": 13102},
+                    "geometry": {"type": "Polygon", "coordinates": [[[139, 35], [140, 36], [139, 36], [139, 35]]]}
+                },
+                {
+                    "type": "Feature",
+                    "id": "sea",
+                    "properties": {},
+                    "geometry": {"type": "Polygon", "coordinates": [[[139, 34], [140, 34], [140, 35], [139, 34]]]}
+                }
+            ]
+        }"#;
+        let zones = Zone::parse(geojson, "code").unwrap();
+        let ids: Vec<&str> = zones.iter().map(|zone| zone.id.as_str()).collect();
+        assert_eq!(ids, ["13101", "13102", "sea"]);
+        assert!(zones[0].area.contains(139.9, 35.1));
+        assert!(!zones[1].area.contains(139.9, 35.1));
+        let zones = Zone::parse(geojson, "name").unwrap();
+        assert_eq!(zones[0].id, "千代田区");
+        assert_eq!(zones[1].id, "2");
+
+        // フィーチャー・コレクション以外のGeoJSONや、識別子がないフィーチャー
+        assert!(Zone::parse(
+            r#"{"type": "Polygon", "coordinates": [[[139, 35], [140, 35], [140, 36], [139, 35]]]}"#,
+            "code"
+        )
+        .is_err());
+        assert!(Zone::parse(
+            r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {}, "geometry": {"type": "Polygon", "coordinates": [[[139, 35], [140, 35], [140, 36], [139, 35]]]}}]}"#,
+            "code"
+        )
+        .is_err());
+        assert!(Zone::parse(r#"{"type": "FeatureCollection", "features": []}"#, "code").is_err());
+    }
+
+    #[test]
+    fn escape_csv_field() {
+        assert_eq!(csv_field("13101"), "13101");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use grib2csv::{Boundary, Grib2Csv, ValidationPolicy};
+
+const SAMPLE_FILE: &str = "fixtures/sample.bin";
+
+/// テストごとに異なる、一時ディレクトリ内のファイルのパスを返却する。
+///
+/// 同時に実行されたテストが同じファイルを上書きしないように、プロセスIDと通し番号をファイル名に付与する。
+fn temp_path(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let number = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    std::env::temp_dir().join(format!("{}_{number}_{name}", std::process::id()))
+}
+
+/// GRIB2ファイルを変換して、CSVファイルの内容を返却する。
+fn convert<P: AsRef<Path>>(input: P, name: &str) -> String {
+    convert_with(&Grib2Csv::new(input, true).unwrap(), name)
+}
+
+/// コンバーターでGRIB2報を変換して、CSVファイルの内容を返却する。
+fn convert_with<R: std::io::BufRead + std::io::Seek>(grib2: &Grib2Csv<R>, name: &str) -> String {
+    let output = temp_path(&format!("{name}.csv"));
+    grib2.convert(&output, Boundary::default()).unwrap();
+    let csv = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(output).unwrap();
+
+    csv
+}
+
+/// 圧縮したサンプルファイルを変換した結果が、圧縮していないサンプルファイルを変換した結果と一致するか確認する。
+fn assert_same_as_uncompressed(file_name: &str, compressed: Vec<u8>) {
+    let expected = convert(SAMPLE_FILE, &format!("{file_name}.expected"));
+    let input = temp_path(file_name);
+    std::fs::write(&input, &compressed).unwrap();
+    let actual = convert(&input, file_name);
+    std::fs::remove_file(input).unwrap();
+    assert_eq!(actual, expected);
+
+    // メモリ上の圧縮されたバイト列から変換
+    let grib2 = Grib2Csv::from_bytes(&compressed, true, ValidationPolicy::default()).unwrap();
+    assert_eq!(convert_with(&grib2, file_name), expected);
+}
+
+#[test]
+fn can_convert_gzip_compressed_file() {
+    let sample = std::fs::read(SAMPLE_FILE).unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&sample).unwrap();
+
+    assert_same_as_uncompressed("grib2csv_sample.bin.gz", encoder.finish().unwrap());
+}
+
+#[test]
+fn can_convert_bzip2_compressed_file() {
+    let sample = std::fs::read(SAMPLE_FILE).unwrap();
+    let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+    encoder.write_all(&sample).unwrap();
+
+    assert_same_as_uncompressed("grib2csv_sample.bin.bz2", encoder.finish().unwrap());
+}
+
+#[test]
+fn can_convert_xz_compressed_file() {
+    let sample = std::fs::read(SAMPLE_FILE).unwrap();
+    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+    encoder.write_all(&sample).unwrap();
+
+    assert_same_as_uncompressed("grib2csv_sample.bin.xz", encoder.finish().unwrap());
+}
+
+#[test]
+fn reject_corrupted_compressed_file() {
+    // gzip形式のマジックナンバーに続く壊れたデータ
+    let input = temp_path("grib2csv_corrupted.bin.gz");
+    std::fs::write(&input, [0x1f, 0x8b, 0x08, 0x00, 0xff, 0xff]).unwrap();
+    assert!(Grib2Csv::new(&input, true).is_err());
+    std::fs::remove_file(input).unwrap();
+}
@@ -0,0 +1,108 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use grib2csv::{Boundary, Grib2Archive, Grib2Csv};
+use time::macros::datetime;
+
+const SAMPLE_FILE: &str = "fixtures/sample.bin";
+
+/// テストごとに異なる、一時ディレクトリ内のファイルのパスを返却する。
+///
+/// 同時に実行されたテストが同じファイルを上書きしないように、プロセスIDと通し番号をファイル名に付与する。
+fn temp_path(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let number = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    std::env::temp_dir().join(format!("{}_{number}_{name}", std::process::id()))
+}
+
+/// GRIB2ファイルを変換して、CSVファイルの内容を返却する。
+fn convert<R: std::io::BufRead + std::io::Seek>(grib2: &Grib2Csv<R>, name: &str) -> String {
+    let output = temp_path(&format!("{name}.csv"));
+    grib2.convert(&output, Boundary::default()).unwrap();
+    let csv = std::fs::read_to_string(&output).unwrap();
+    std::fs::remove_file(output).unwrap();
+
+    csv
+}
+
+/// サンプルファイル、gzip形式で圧縮したサンプルファイル及びテキストファイルを格納したtarアーカイブを作成する。
+fn build_archive(file_name: &str, compress: bool) -> PathBuf {
+    let sample = std::fs::read(SAMPLE_FILE).unwrap();
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(&sample).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let text = b"not a grib2 file\n";
+
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, bytes) in [
+        ("data/sample.bin", &sample[..]),
+        ("data/readme.txt", &text[..]),
+        ("data/sample.bin.gz", &compressed[..]),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bytes.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, bytes).unwrap();
+    }
+    let mut archive = builder.into_inner().unwrap();
+    if compress {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(&archive).unwrap();
+        archive = encoder.finish().unwrap();
+    }
+    let path = temp_path(file_name);
+    std::fs::write(&path, archive).unwrap();
+
+    path
+}
+
+/// tarアーカイブに格納されたGRIB2ファイルを一覧し、変換した結果がサンプルファイルを変換した結果と一致するか確認する。
+fn assert_archive(path: &Path, name: &str) {
+    let expected = convert(
+        &Grib2Csv::new(SAMPLE_FILE, true).unwrap(),
+        &format!("{name}.expected"),
+    );
+    let archive = Grib2Archive::new(path, true);
+
+    let members = archive.members().unwrap();
+    let names: Vec<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["data/sample.bin", "data/sample.bin.gz"]);
+    for member in &members {
+        assert_eq!(member.referenced_at, datetime!(2021-08-17 09:00:00));
+        assert_eq!(member.number_of_messages, 1);
+    }
+
+    for member in &members {
+        let grib2 = archive.member(&member.name).unwrap();
+        assert_eq!(convert(&grib2, name), expected);
+    }
+    let mut count = 0;
+    archive
+        .for_each(|_, grib2| {
+            assert_eq!(convert(&grib2, name), expected);
+            count += 1;
+            Ok(true)
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+
+    assert!(archive.member("data/readme.txt").is_err());
+    assert!(archive.member("data/missing.bin").is_err());
+}
+
+#[test]
+fn can_convert_members_of_tar_archive() {
+    let path = build_archive("grib2csv_archive.tar", false);
+    assert_archive(&path, "grib2csv_archive.tar");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn can_convert_members_of_compressed_tar_archive() {
+    let path = build_archive("grib2csv_archive.tar.gz", true);
+    assert_archive(&path, "grib2csv_archive.tar.gz");
+    std::fs::remove_file(path).unwrap();
+}